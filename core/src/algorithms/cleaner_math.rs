@@ -0,0 +1,20 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+pub fn last_use(accessed: Option<u64>, modified: u64) -> u64 {
+    accessed.map_or(modified, |atime| atime.max(modified))
+}
+
+pub fn eviction_cutoff(samples: &mut [(u64, u64)], used: u64, quota: u64) -> Option<u64> {
+    if used <= quota || samples.is_empty() {
+        return None;
+    }
+    samples.sort_unstable_by_key(|&(last_use, _)| last_use);
+    let mut excess = used - quota;
+    for &(last_use, size) in samples.iter() {
+        excess = excess.saturating_sub(size);
+        if excess == 0 {
+            return Some(last_use);
+        }
+    }
+    samples.last().map(|&(last_use, _)| last_use)
+}
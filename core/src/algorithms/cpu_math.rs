@@ -2,6 +2,8 @@
 
 use crate::utils::tier::DeviceTier;
 
+const UCLAMP_CAPACITY_SCALE: f32 = 1024.0;
+
 #[derive(Debug, Clone, Copy)]
 pub struct LoadState {
     pub psi_value: f32,
@@ -35,6 +37,10 @@ pub struct CpuKernelLimits {
     pub max_walt_init_pct: f32,
     pub min_uclamp_min: f32,
     pub max_uclamp_min: f32,
+    pub min_bg_uclamp_max: f32,
+    pub max_bg_uclamp_max: f32,
+    pub min_base_slice_ns: f32,
+    pub max_base_slice_ns: f32,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -59,6 +65,11 @@ pub struct CpuMathConfig {
     pub transient_poll_interval: f32,
     pub nis_threshold: f32,
     pub bat_level_weight: f32,
+    pub util_knee: f32,
+    pub util_weight: f32,
+    pub power_knee_mw: f32,
+    pub power_span_mw: f32,
+    pub power_weight: f32,
 }
 
 impl Default for CpuMathConfig {
@@ -86,6 +97,11 @@ impl Default for CpuMathConfig {
                 transient_poll_interval: 45.0,
                 nis_threshold: 6.5,
                 bat_level_weight: 94.0,
+                util_knee: 0.55,
+                util_weight: 12.0,
+                power_knee_mw: 3500.0,
+                power_span_mw: 6000.0,
+                power_weight: 30.0,
             },
             DeviceTier::MidRange => Self {
                 latency_gran_ratio: 0.33,
@@ -108,6 +124,11 @@ impl Default for CpuMathConfig {
                 transient_poll_interval: 48.0,
                 nis_threshold: 7.2,
                 bat_level_weight: 95.5,
+                util_knee: 0.6,
+                util_weight: 11.0,
+                power_knee_mw: 2500.0,
+                power_span_mw: 4500.0,
+                power_weight: 35.0,
             },
             DeviceTier::LowEnd => Self {
                 latency_gran_ratio: 0.34,
@@ -130,6 +151,11 @@ impl Default for CpuMathConfig {
                 transient_poll_interval: 52.0,
                 nis_threshold: 7.8,
                 bat_level_weight: 97.0,
+                util_knee: 0.65,
+                util_weight: 10.0,
+                power_knee_mw: 1800.0,
+                power_span_mw: 3200.0,
+                power_weight: 40.0,
             },
         }
     }
@@ -146,6 +172,16 @@ pub struct DemandInput {
     pub integral_total: f32,
     pub integral_dot: f32,
     pub is_structural_break: bool,
+    pub utilization: f32,
+    pub peak_core_utilization: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CgroupUclampTargets {
+    pub top_app_min: f32,
+    pub foreground_min: f32,
+    pub background_max: f32,
+    pub system_background_max: f32,
 }
 
 #[inline]
@@ -179,15 +215,23 @@ pub fn is_transient(state: &LoadState, target_psi: f32, math_config: &CpuMathCon
         || (state.psi_value - target_psi).abs() > math_config.transient_diff_threshold
 }
 
+#[inline]
+pub fn calculate_power_cost(discharge_mw: f32, math_config: &CpuMathConfig) -> f32 {
+    let span = math_config.power_span_mw.max(1.0);
+    let excess = ((discharge_mw - math_config.power_knee_mw) / span).clamp(0.0, 1.0);
+    math_config.power_weight * excess.powi(2)
+}
+
 pub fn update_integral_params(
     state: &mut LoadState,
     bat_level: f32,
+    discharge_mw: f32,
     dt_safe: f32,
     math_config: &CpuMathConfig,
 ) -> (f32, f32) {
     let depletion = (100.0 - bat_level).max(0.0) / 100.0;
     let cost_heuristic = math_config.bat_level_weight * depletion.powi(3);
-    let total_integral = cost_heuristic;
+    let total_integral = cost_heuristic + calculate_power_cost(discharge_mw, math_config);
     if state.first_run {
         state.prev_integral = total_integral;
         state.first_run = false;
@@ -202,6 +246,18 @@ pub fn update_integral_params(
     (total_integral, integral_dot)
 }
 
+#[inline]
+pub fn calculate_utilization_demand(
+    utilization: f32,
+    peak_core_utilization: f32,
+    math_config: &CpuMathConfig,
+) -> f32 {
+    let util_signal = 0.5 * (utilization + peak_core_utilization);
+    let span = (1.0 - math_config.util_knee).max(0.01);
+    let excess = ((util_signal - math_config.util_knee) / span).clamp(0.0, 1.0);
+    excess * math_config.util_weight
+}
+
 pub fn calculate_load_demand(
     state: &mut LoadState,
     input: DemandInput,
@@ -215,7 +271,10 @@ pub fn calculate_load_demand(
     if load_rate.abs() > math_config.surge_threshold {
         state.rate += load_rate * math_config.surge_gain;
     }
-    let prediction_target = input.target_psi + (load_rate * math_config.lookahead_time);
+    let util_demand =
+        calculate_utilization_demand(input.utilization, input.peak_core_utilization, math_config);
+    let prediction_target =
+        input.target_psi.max(util_demand) + (load_rate * math_config.lookahead_time);
     let k_base = math_config.response_gain;
     let k_dynamic = k_base * (1.0 + (math_config.gain_scheduling_alpha * input.trend_factor));
     let k_final = k_dynamic * input.thermal_scale.clamp(0.1, 1.0).powi(2);
@@ -352,3 +411,60 @@ pub fn calculate_uclamp_min(
     let ideal_uclamp = kernel_limits.min_uclamp_min + (range * sigmoid_val);
     (ideal_uclamp * thermal_scale).clamp(kernel_limits.min_uclamp_min, kernel_limits.max_uclamp_min)
 }
+
+#[inline]
+pub fn calculate_base_slice(granularity_ns: f32, kernel_limits: &CpuKernelLimits) -> f32 {
+    let gran_range = kernel_limits.max_granularity_ns - kernel_limits.min_granularity_ns;
+    let ratio = if gran_range > 0.0 {
+        ((granularity_ns - kernel_limits.min_granularity_ns) / gran_range).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let slice_range = kernel_limits.max_base_slice_ns - kernel_limits.min_base_slice_ns;
+    kernel_limits.min_base_slice_ns + slice_range * ratio
+}
+
+#[inline]
+pub fn resolve_run_to_parity(
+    wakeup_ns: f32,
+    current: bool,
+    kernel_limits: &CpuKernelLimits,
+) -> bool {
+    let range = kernel_limits.max_wakeup_ns - kernel_limits.min_wakeup_ns;
+    if range <= 0.0 {
+        return current;
+    }
+    let ratio = (wakeup_ns - kernel_limits.min_wakeup_ns) / range;
+    if current { ratio > 0.35 } else { ratio > 0.65 }
+}
+
+pub fn calculate_cgroup_uclamp(
+    uclamp_min: f32,
+    thermal_scale: f32,
+    kernel_limits: &CpuKernelLimits,
+) -> CgroupUclampTargets {
+    let top_app_min = (uclamp_min / UCLAMP_CAPACITY_SCALE * 100.0).clamp(0.0, 100.0);
+    let boost_range = kernel_limits.max_uclamp_min - kernel_limits.min_uclamp_min;
+    let boost_ratio = if boost_range > 0.0 {
+        ((uclamp_min - kernel_limits.min_uclamp_min) / boost_range).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let headroom = (1.0 - boost_ratio) * thermal_scale.clamp(0.0, 1.0);
+    let cap_range = kernel_limits.max_bg_uclamp_max - kernel_limits.min_bg_uclamp_max;
+    let background_max = kernel_limits.min_bg_uclamp_max + cap_range * headroom;
+    let system_background_max =
+        background_max + (kernel_limits.max_bg_uclamp_max - background_max) * 0.5;
+    CgroupUclampTargets {
+        top_app_min,
+        foreground_min: top_app_min * 0.5,
+        background_max: background_max.clamp(
+            kernel_limits.min_bg_uclamp_max,
+            kernel_limits.max_bg_uclamp_max,
+        ),
+        system_background_max: system_background_max.clamp(
+            kernel_limits.min_bg_uclamp_max,
+            kernel_limits.max_bg_uclamp_max,
+        ),
+    }
+}
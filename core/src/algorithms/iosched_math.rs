@@ -0,0 +1,287 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use std::time;
+
+pub const SCHEDULER_CANDIDATES: &[&str] = &["mq-deadline", "kyber", "bfq", "none"];
+
+const WINDOW_SIZE: usize = 64;
+const CLASS_COUNT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkloadClass {
+    Random,
+    Mixed,
+    Sequential,
+}
+
+impl WorkloadClass {
+    pub fn from_sequentiality(sequentiality: f32) -> Self {
+        if sequentiality < 0.33 {
+            WorkloadClass::Random
+        } else if sequentiality < 0.66 {
+            WorkloadClass::Mixed
+        } else {
+            WorkloadClass::Sequential
+        }
+    }
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WorkloadClass::Random => "random",
+            WorkloadClass::Mixed => "mixed",
+            WorkloadClass::Sequential => "sequential",
+        }
+    }
+    fn index(self) -> usize {
+        match self {
+            WorkloadClass::Random => 0,
+            WorkloadClass::Mixed => 1,
+            WorkloadClass::Sequential => 2,
+        }
+    }
+    fn from_index(index: usize) -> Self {
+        match index {
+            0 => WorkloadClass::Random,
+            1 => WorkloadClass::Mixed,
+            _ => WorkloadClass::Sequential,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct IoSchedSelectConfig {
+    pub cooldown: time::Duration,
+    pub trial: time::Duration,
+    pub min_samples: usize,
+    pub revert_margin: f32,
+    pub improve_margin: f32,
+    pub score_alpha: f32,
+    pub max_trial_extensions: u32,
+}
+
+impl Default for IoSchedSelectConfig {
+    fn default() -> Self {
+        Self {
+            cooldown: time::Duration::from_mins(15),
+            trial: time::Duration::from_secs(90),
+            min_samples: 24,
+            revert_margin: 0.15,
+            improve_margin: 0.10,
+            score_alpha: 0.3,
+            max_trial_extensions: 2,
+        }
+    }
+}
+
+struct LatencyWindow {
+    samples: [f32; WINDOW_SIZE],
+    len: usize,
+    pos: usize,
+    class_votes: [u32; CLASS_COUNT],
+}
+
+impl LatencyWindow {
+    fn new() -> Self {
+        Self {
+            samples: [0.0; WINDOW_SIZE],
+            len: 0,
+            pos: 0,
+            class_votes: [0; CLASS_COUNT],
+        }
+    }
+    fn push(&mut self, value: f32, class: WorkloadClass) {
+        self.samples[self.pos] = value;
+        self.pos = (self.pos + 1) % WINDOW_SIZE;
+        self.len = (self.len + 1).min(WINDOW_SIZE);
+        self.class_votes[class.index()] += 1;
+    }
+    fn clear(&mut self) {
+        self.len = 0;
+        self.pos = 0;
+        self.class_votes = [0; CLASS_COUNT];
+    }
+    fn percentile(&self, p: f32) -> Option<f32> {
+        if self.len == 0 {
+            return None;
+        }
+        let mut sorted = self.samples;
+        let slice = &mut sorted[..self.len];
+        slice.sort_unstable_by(f32::total_cmp);
+        let rank = ((self.len - 1) as f32 * p.clamp(0.0, 1.0)).round() as usize;
+        Some(slice[rank])
+    }
+    fn dominant_class(&self) -> WorkloadClass {
+        let index = (0..CLASS_COUNT)
+            .max_by_key(|&i| self.class_votes[i])
+            .unwrap_or(0);
+        WorkloadClass::from_index(index)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Phase {
+    Baseline,
+    Trial {
+        from: usize,
+        class: WorkloadClass,
+        extensions: u32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectorDecision {
+    Stay,
+    Switch(&'static str),
+    Revert(&'static str),
+}
+
+pub struct SchedulerSelector {
+    available: Vec<usize>,
+    current: usize,
+    scores: [[Option<f32>; CLASS_COUNT]; SCHEDULER_CANDIDATES.len()],
+    window: LatencyWindow,
+    phase: Phase,
+    phase_start: time::Instant,
+    last_switch: time::Instant,
+    last_p90: f32,
+}
+
+fn candidate_index(name: &str) -> Option<usize> {
+    SCHEDULER_CANDIDATES.iter().position(|&c| c == name)
+}
+
+impl SchedulerSelector {
+    pub fn new(available: &[String], current: &str, now: time::Instant) -> Option<Self> {
+        let current = candidate_index(current)?;
+        let available: Vec<usize> = available
+            .iter()
+            .filter_map(|name| candidate_index(name))
+            .collect();
+        if available.len() < 2 {
+            return None;
+        }
+        Some(Self {
+            available,
+            current,
+            scores: [[None; CLASS_COUNT]; SCHEDULER_CANDIDATES.len()],
+            window: LatencyWindow::new(),
+            phase: Phase::Baseline,
+            phase_start: now,
+            last_switch: now,
+            last_p90: 0.0,
+        })
+    }
+    pub fn current(&self) -> &'static str {
+        SCHEDULER_CANDIDATES[self.current]
+    }
+    pub fn is_trial(&self) -> bool {
+        matches!(self.phase, Phase::Trial { .. })
+    }
+    pub fn last_p90(&self) -> f32 {
+        self.last_p90
+    }
+    pub fn observe(&mut self, service_ms: f32, sequentiality: f32) {
+        if service_ms.is_finite() && service_ms > 0.0 {
+            self.window
+                .push(service_ms, WorkloadClass::from_sequentiality(sequentiality));
+        }
+    }
+    fn record_score(&mut self, index: usize, class: WorkloadClass, p90: f32, alpha: f32) {
+        let slot = &mut self.scores[index][class.index()];
+        *slot = Some(slot.map_or(p90, |old| old + alpha * (p90 - old)));
+    }
+    fn pick_candidate(
+        &self,
+        class: WorkloadClass,
+        p90: f32,
+        cfg: &IoSchedSelectConfig,
+    ) -> Option<usize> {
+        let others = || {
+            self.available
+                .iter()
+                .copied()
+                .filter(|&i| i != self.current)
+        };
+        others()
+            .find(|&i| self.scores[i][class.index()].is_none())
+            .or_else(|| {
+                others()
+                    .filter_map(|i| Some((i, self.scores[i][class.index()]?)))
+                    .filter(|&(_, score)| score < p90 * (1.0 - cfg.improve_margin))
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(i, _)| i)
+            })
+    }
+    pub fn evaluate(&mut self, now: time::Instant, cfg: &IoSchedSelectConfig) -> SelectorDecision {
+        if self.window.len < cfg.min_samples {
+            return SelectorDecision::Stay;
+        }
+        let Some(p90) = self.window.percentile(0.9) else {
+            return SelectorDecision::Stay;
+        };
+        let class = self.window.dominant_class();
+        match self.phase {
+            Phase::Baseline => {
+                if now.duration_since(self.last_switch) < cfg.cooldown {
+                    return SelectorDecision::Stay;
+                }
+                self.last_p90 = p90;
+                self.record_score(self.current, class, p90, cfg.score_alpha);
+                let Some(to) = self.pick_candidate(class, p90, cfg) else {
+                    self.last_switch = now;
+                    self.window.clear();
+                    return SelectorDecision::Stay;
+                };
+                self.phase = Phase::Trial {
+                    from: self.current,
+                    class,
+                    extensions: 0,
+                };
+                SelectorDecision::Switch(SCHEDULER_CANDIDATES[to])
+            }
+            Phase::Trial {
+                from,
+                class: trial_class,
+                extensions,
+            } => {
+                if now.duration_since(self.phase_start) < cfg.trial {
+                    return SelectorDecision::Stay;
+                }
+                self.last_p90 = p90;
+                self.record_score(self.current, class, p90, cfg.score_alpha);
+                self.window.clear();
+                self.phase_start = now;
+                let baseline = self.scores[from][class.index()];
+                if class != trial_class && extensions < cfg.max_trial_extensions {
+                    self.phase = Phase::Trial {
+                        from,
+                        class: trial_class,
+                        extensions: extensions + 1,
+                    };
+                    return SelectorDecision::Stay;
+                }
+                self.phase = Phase::Baseline;
+                match baseline {
+                    Some(score) if p90 <= score * (1.0 + cfg.revert_margin) => {
+                        SelectorDecision::Stay
+                    }
+                    _ => SelectorDecision::Revert(SCHEDULER_CANDIDATES[from]),
+                }
+            }
+        }
+    }
+    pub fn switched(&mut self, name: &str, now: time::Instant) {
+        if let Some(index) = candidate_index(name) {
+            self.current = index;
+        }
+        self.window.clear();
+        self.phase_start = now;
+        self.last_switch = now;
+    }
+    pub fn switch_failed(&mut self, name: &str) {
+        if let Some(index) = candidate_index(name) {
+            self.scores[index] = [Some(f32::INFINITY); CLASS_COUNT];
+            self.available.retain(|&i| i != index);
+        }
+        self.phase = Phase::Baseline;
+    }
+}
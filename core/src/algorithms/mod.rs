@@ -1,5 +1,9 @@
+pub mod cleaner_math;
 pub mod cpu_math;
 pub mod filter_math;
+pub mod iosched_math;
 pub mod poll_math;
 pub mod storage_math;
 pub mod thermal_math;
+pub mod thermal_tuning;
+pub mod zram_math;
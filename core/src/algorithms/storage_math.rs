@@ -11,6 +11,35 @@ pub struct StorageKernelLimits {
     pub max_nr_requests: f32,
 }
 
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WritebackLimits {
+    pub min_dirty_ratio: f32,
+    pub max_dirty_ratio: f32,
+    pub min_dirty_background_ratio: f32,
+    pub max_dirty_background_ratio: f32,
+    pub min_dirty_expire_cs: f32,
+    pub max_dirty_expire_cs: f32,
+    pub min_dirty_writeback_cs: f32,
+    pub max_dirty_writeback_cs: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WritebackTargets {
+    pub dirty_ratio: f32,
+    pub dirty_background_ratio: f32,
+    pub dirty_expire_cs: f32,
+    pub dirty_writeback_cs: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct WritebackInput {
+    pub io_psi: f32,
+    pub mem_psi: f32,
+    pub write_kbps: f32,
+    pub screen_on: bool,
+    pub charging: bool,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct StorageMathConfig {
     pub min_req_size_kb: f32,
@@ -23,6 +52,29 @@ pub struct StorageMathConfig {
     pub queue_pressure_high: f32,
     pub smoothing_factor: f32,
     pub idle_poll_interval: f32,
+    pub writeback_busy_kbps: f32,
+    pub writeback_mem_psi_high: f32,
+    pub writeback_io_psi_high: f32,
+    pub kyber_read_lat_fraction: f32,
+    pub deadline_min_read_expire_ms: f32,
+    pub deadline_max_read_expire_ms: f32,
+    pub deadline_min_fifo_batch: f32,
+    pub deadline_max_fifo_batch: f32,
+    pub bfq_interactive_seq_max: f32,
+    pub discard_heavy_ratio: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KyberTargets {
+    pub read_lat_nsec: f32,
+    pub write_lat_nsec: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeadlineTargets {
+    pub read_expire_ms: f32,
+    pub write_expire_ms: f32,
+    pub fifo_batch: f32,
 }
 
 impl Default for StorageMathConfig {
@@ -40,6 +92,16 @@ impl Default for StorageMathConfig {
                 queue_pressure_high: 6.0,
                 smoothing_factor: 0.55,
                 idle_poll_interval: 5000.0,
+                writeback_busy_kbps: 16384.0,
+                writeback_mem_psi_high: 8.0,
+                writeback_io_psi_high: 20.0,
+                kyber_read_lat_fraction: 0.08,
+                deadline_min_read_expire_ms: 100.0,
+                deadline_max_read_expire_ms: 500.0,
+                deadline_min_fifo_batch: 4.0,
+                deadline_max_fifo_batch: 64.0,
+                bfq_interactive_seq_max: 0.35,
+                discard_heavy_ratio: 0.3,
             },
             DeviceTier::MidRange => Self {
                 min_req_size_kb: 7.0,
@@ -52,6 +114,16 @@ impl Default for StorageMathConfig {
                 queue_pressure_high: 5.5,
                 smoothing_factor: 0.52,
                 idle_poll_interval: 5000.0,
+                writeback_busy_kbps: 12288.0,
+                writeback_mem_psi_high: 7.0,
+                writeback_io_psi_high: 20.0,
+                kyber_read_lat_fraction: 0.08,
+                deadline_min_read_expire_ms: 120.0,
+                deadline_max_read_expire_ms: 500.0,
+                deadline_min_fifo_batch: 4.0,
+                deadline_max_fifo_batch: 48.0,
+                bfq_interactive_seq_max: 0.35,
+                discard_heavy_ratio: 0.3,
             },
             DeviceTier::LowEnd => Self {
                 min_req_size_kb: 8.0,
@@ -64,6 +136,16 @@ impl Default for StorageMathConfig {
                 queue_pressure_high: 5.0,
                 smoothing_factor: 0.5,
                 idle_poll_interval: 5000.0,
+                writeback_busy_kbps: 8192.0,
+                writeback_mem_psi_high: 6.0,
+                writeback_io_psi_high: 22.0,
+                kyber_read_lat_fraction: 0.1,
+                deadline_min_read_expire_ms: 150.0,
+                deadline_max_read_expire_ms: 500.0,
+                deadline_min_fifo_batch: 4.0,
+                deadline_max_fifo_batch: 32.0,
+                bfq_interactive_seq_max: 0.4,
+                discard_heavy_ratio: 0.3,
             },
         }
     }
@@ -77,6 +159,16 @@ pub struct IoDelta {
     pub delta_read_ios: f32,
     pub delta_read_merges: f32,
     pub delta_read_sectors: f32,
+    pub write_kbps: f32,
+    pub read_service_ms: f32,
+    pub write_service_ms: f32,
+    pub discard_iops: f32,
+    pub discard_kbps: f32,
+    pub discard_busy_ratio: f32,
+    pub flush_iops: f32,
+    pub flush_service_ms: f32,
+    pub has_discard: bool,
+    pub has_flush: bool,
 }
 
 pub struct WorkloadState {
@@ -127,12 +219,21 @@ pub fn calculate_io_deltas(
     let delta_read_merges = current.read_merges.saturating_sub(prev.read_merges) as f32;
     let delta_read_sectors = current.read_sectors.saturating_sub(prev.read_sectors) as f32;
     let delta_write_ios = current.write_ios.saturating_sub(prev.write_ios) as f32;
+    let delta_write_sectors = current.write_sectors.saturating_sub(prev.write_sectors) as f32;
     let delta_write_ticks = current.write_ticks.saturating_sub(prev.write_ticks) as f32;
     let delta_read_ticks = current.read_ticks.saturating_sub(prev.read_ticks) as f32;
+    let delta_discard_ios = current.discard_ios.saturating_sub(prev.discard_ios) as f32;
+    let delta_discard_sectors = current.discard_sectors.saturating_sub(prev.discard_sectors) as f32;
+    let delta_discard_ticks = current.discard_ticks.saturating_sub(prev.discard_ticks) as f32;
+    let delta_flush_ios = current.flush_ios.saturating_sub(prev.flush_ios) as f32;
+    let delta_flush_ticks = current.flush_ticks.saturating_sub(prev.flush_ticks) as f32;
+    let per_io = |ticks: f32, ios: f32| if ios > 0.0 { ticks / ios } else { 0.0 };
     let total_ios = delta_read_ios + delta_write_ios;
     let total_ticks = delta_read_ticks + delta_write_ticks;
-    let service_time_ms = if total_ios > 0.0 {
-        total_ticks / total_ios
+    let service_time_ms = per_io(total_ticks, total_ios);
+    let all_ticks = total_ticks + delta_discard_ticks;
+    let discard_busy_ratio = if all_ticks > 0.0 {
+        delta_discard_ticks / all_ticks
     } else {
         0.0
     };
@@ -143,6 +244,16 @@ pub fn calculate_io_deltas(
         delta_read_ios,
         delta_read_merges,
         delta_read_sectors,
+        write_kbps: delta_write_sectors * 0.5 / dt_real,
+        read_service_ms: per_io(delta_read_ticks, delta_read_ios),
+        write_service_ms: per_io(delta_write_ticks, delta_write_ios),
+        discard_iops: delta_discard_ios / dt_real,
+        discard_kbps: delta_discard_sectors * 0.5 / dt_real,
+        discard_busy_ratio,
+        flush_iops: delta_flush_ios / dt_real,
+        flush_service_ms: per_io(delta_flush_ticks, delta_flush_ios),
+        has_discard: current.has_discard(),
+        has_flush: current.has_flush(),
     }
 }
 
@@ -200,6 +311,11 @@ pub fn calculate_weighted_throughput(delta: &IoDelta, math_config: &StorageMathC
     delta.throughput_read + (math_config.write_cost_factor * delta.throughput_write)
 }
 
+#[inline]
+pub fn is_discard_heavy(delta: &IoDelta, math_config: &StorageMathConfig) -> bool {
+    delta.has_discard && delta.discard_busy_ratio > math_config.discard_heavy_ratio
+}
+
 #[inline]
 pub fn calculate_effective_latency(delta: &IoDelta, lambda_eff: f32, in_flight: f32) -> f32 {
     if delta.service_time_ms > 0.0 {
@@ -250,3 +366,83 @@ pub fn calculate_next_queue_depth(
     };
     next_nr.clamp(kernel_limits.min_nr_requests, kernel_limits.max_nr_requests)
 }
+
+pub fn calculate_writeback_targets(
+    input: WritebackInput,
+    math_config: &StorageMathConfig,
+    limits: &WritebackLimits,
+) -> WritebackTargets {
+    let activity = (input.write_kbps / math_config.writeback_busy_kbps.max(1.0)).clamp(0.0, 1.0);
+    let mut batching = 1.0 - activity;
+    if input.screen_on {
+        batching *= 0.4;
+    }
+    if input.charging {
+        batching *= 0.5;
+    }
+    let mem_urgency = (input.mem_psi / math_config.writeback_mem_psi_high.max(0.1)).clamp(0.0, 1.0);
+    let io_urgency = (input.io_psi / math_config.writeback_io_psi_high.max(0.1)).clamp(0.0, 1.0);
+    let urgency = mem_urgency.max(0.5 * io_urgency);
+    let level = batching * (1.0 - urgency);
+    let lerp = |min: f32, max: f32| min + level * (max - min);
+    let dirty_ratio = lerp(limits.min_dirty_ratio, limits.max_dirty_ratio);
+    let dirty_background_ratio = lerp(
+        limits.min_dirty_background_ratio,
+        limits.max_dirty_background_ratio,
+    )
+    .min(dirty_ratio * 0.5);
+    WritebackTargets {
+        dirty_ratio,
+        dirty_background_ratio,
+        dirty_expire_cs: lerp(limits.min_dirty_expire_cs, limits.max_dirty_expire_cs),
+        dirty_writeback_cs: lerp(limits.min_dirty_writeback_cs, limits.max_dirty_writeback_cs),
+    }
+}
+
+#[inline]
+pub fn calculate_kyber_targets(
+    target_latency_ms: f32,
+    math_config: &StorageMathConfig,
+) -> KyberTargets {
+    let read_ms = (target_latency_ms * math_config.kyber_read_lat_fraction).clamp(1.0, 20.0);
+    let write_ms = read_ms * math_config.write_cost_factor;
+    KyberTargets {
+        read_lat_nsec: read_ms * 1_000_000.0,
+        write_lat_nsec: write_ms * 1_000_000.0,
+    }
+}
+
+#[inline]
+pub fn calculate_deadline_targets(
+    sequentiality: f32,
+    math_config: &StorageMathConfig,
+) -> DeadlineTargets {
+    let seq = sequentiality.clamp(0.0, 1.0);
+    let read_expire_ms = math_config.deadline_min_read_expire_ms
+        + seq * (math_config.deadline_max_read_expire_ms - math_config.deadline_min_read_expire_ms);
+    let fifo_batch = math_config.deadline_min_fifo_batch
+        + seq * (math_config.deadline_max_fifo_batch - math_config.deadline_min_fifo_batch);
+    DeadlineTargets {
+        read_expire_ms,
+        write_expire_ms: read_expire_ms * 10.0,
+        fifo_batch,
+    }
+}
+
+#[inline]
+pub fn should_enable_bfq_low_latency(
+    current: bool,
+    sequentiality: f32,
+    screen_on: bool,
+    math_config: &StorageMathConfig,
+) -> bool {
+    if !screen_on {
+        return false;
+    }
+    let threshold = if current {
+        math_config.bfq_interactive_seq_max + 0.2
+    } else {
+        math_config.bfq_interactive_seq_max
+    };
+    sequentiality < threshold
+}
@@ -1,5 +1,7 @@
 //! Author: [Seclususs](https://github.com/seclususs)
 
+use crate::hal::thermal;
+use crate::resources::discovery;
 use crate::utils::tier::DeviceTier;
 
 use std::time;
@@ -26,6 +28,13 @@ pub struct ThermalConfig {
     pub smith_gain: f32,
     pub smith_tau: f32,
     pub smith_delay_sec: f32,
+    pub gpu_zone_bias: f32,
+    pub skin_zone_bias: f32,
+    pub modem_zone_bias: f32,
+    pub zone_peak_weight: f32,
+    pub bat_thermal_tau: f32,
+    pub forecast_horizon_sec: f32,
+    pub forecast_max_cut: f32,
 }
 
 impl Default for ThermalConfig {
@@ -51,6 +60,13 @@ impl Default for ThermalConfig {
                 smith_gain: 1.75,
                 smith_tau: 9.5,
                 smith_delay_sec: 1.4,
+                gpu_zone_bias: -4.0,
+                skin_zone_bias: 14.0,
+                modem_zone_bias: 6.0,
+                zone_peak_weight: 0.75,
+                bat_thermal_tau: 150.0,
+                forecast_horizon_sec: 30.0,
+                forecast_max_cut: 0.25,
             },
             DeviceTier::MidRange => Self {
                 hard_limit_cpu: 53.5,
@@ -71,6 +87,13 @@ impl Default for ThermalConfig {
                 smith_gain: 1.62,
                 smith_tau: 10.5,
                 smith_delay_sec: 1.6,
+                gpu_zone_bias: -4.0,
+                skin_zone_bias: 13.0,
+                modem_zone_bias: 6.0,
+                zone_peak_weight: 0.75,
+                bat_thermal_tau: 170.0,
+                forecast_horizon_sec: 35.0,
+                forecast_max_cut: 0.25,
             },
             DeviceTier::LowEnd => Self {
                 hard_limit_cpu: 52.5,
@@ -91,11 +114,92 @@ impl Default for ThermalConfig {
                 smith_gain: 1.52,
                 smith_tau: 11.5,
                 smith_delay_sec: 1.7,
+                gpu_zone_bias: -3.0,
+                skin_zone_bias: 12.0,
+                modem_zone_bias: 5.0,
+                zone_peak_weight: 0.7,
+                bat_thermal_tau: 190.0,
+                forecast_horizon_sec: 40.0,
+                forecast_max_cut: 0.3,
             },
         }
     }
 }
 
+pub fn aggregate_zone_temps(
+    readings: &[thermal::ZoneReading],
+    tunables: &ThermalConfig,
+) -> Option<f32> {
+    let mut peak = f32::MIN;
+    let mut sum = 0.0;
+    let mut count = 0u32;
+    for reading in readings {
+        if reading.health != thermal::SensorHealth::Ok || reading.temp <= 0.0 {
+            continue;
+        }
+        let bias = match reading.class {
+            discovery::ZoneClass::Cpu => 0.0,
+            discovery::ZoneClass::Gpu => tunables.gpu_zone_bias,
+            discovery::ZoneClass::Skin => tunables.skin_zone_bias,
+            discovery::ZoneClass::Modem => tunables.modem_zone_bias,
+            discovery::ZoneClass::Battery => continue,
+        };
+        let equivalent = reading.temp + bias;
+        peak = peak.max(equivalent);
+        sum += equivalent;
+        count += 1;
+    }
+    if count == 0 {
+        return None;
+    }
+    let mean = sum / count as f32;
+    let weight = tunables.zone_peak_weight.clamp(0.0, 1.0);
+    Some(weight * peak + (1.0 - weight) * mean)
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThermalForecast {
+    pub cpu_steady_state: f32,
+    pub bat_steady_state: f32,
+    pub cpu_time_to_limit: Option<f32>,
+    pub bat_time_to_limit: Option<f32>,
+}
+
+impl ThermalForecast {
+    pub fn time_to_throttle(&self) -> Option<f32> {
+        match (self.cpu_time_to_limit, self.bat_time_to_limit) {
+            (Some(cpu), Some(bat)) => Some(cpu.min(bat)),
+            (cpu, bat) => cpu.or(bat),
+        }
+    }
+}
+
+fn project_first_order(temp: f32, steady_state: f32, tau: f32, limit: f32) -> Option<f32> {
+    if temp >= limit {
+        return Some(0.0);
+    }
+    if steady_state <= limit {
+        return None;
+    }
+    let remaining = (steady_state - limit) / (steady_state - temp);
+    Some(-tau.max(0.1) * remaining.ln())
+}
+
+pub fn calculate_forecast_scale(forecast: &ThermalForecast, tunables: &ThermalConfig) -> f32 {
+    let Some(time_to_limit) = forecast.time_to_throttle() else {
+        return 1.0;
+    };
+    let horizon = tunables.forecast_horizon_sec.max(1.0);
+    let urgency = (1.0 - time_to_limit / horizon).clamp(0.0, 1.0);
+    1.0 - urgency * tunables.forecast_max_cut.clamp(0.0, 1.0)
+}
+
+#[inline]
+fn first_order_step(prev: f32, input: f32, dt: f32, tau: f32) -> f32 {
+    let alpha = dt / (tau + dt);
+    alpha * input + (1.0 - alpha) * prev
+}
+
 struct LeadLagFilter {
     prev_y: f32,
     prev_u: f32,
@@ -177,8 +281,7 @@ impl SmithPredictor {
         tau: f32,
         delay_sec: f32,
     ) -> (f32, f32) {
-        let alpha = dt / (tau + dt);
-        let y_no_delay = alpha * (u_control * k_gain) + (1.0 - alpha) * self.model_output_no_delay;
+        let y_no_delay = first_order_step(self.model_output_no_delay, u_control * k_gain, dt, tau);
         self.model_output_no_delay = y_no_delay;
         let now = time::Instant::now();
         self.delay_buffer[self.head] = HistoryPoint {
@@ -213,6 +316,7 @@ pub struct ThermalManager {
     prev_output_sat: f32,
     feedforward: LeadLagFilter,
     smith_predictor: SmithPredictor,
+    forecast: ThermalForecast,
 }
 
 impl Default for ThermalManager {
@@ -231,8 +335,12 @@ impl ThermalManager {
             prev_output_sat: 0.0,
             feedforward: LeadLagFilter::new(),
             smith_predictor: SmithPredictor::new(512),
+            forecast: ThermalForecast::default(),
         }
     }
+    pub fn forecast(&self) -> ThermalForecast {
+        self.forecast
+    }
     pub fn update(
         &mut self,
         cpu_temp: f32,
@@ -274,6 +382,35 @@ impl ThermalManager {
         );
         let pred_error_term = y_pred_no_delay - y_pred_delayed;
         let adjusted_pv = cpu_temp + pred_error_term;
+        // The model settles at smith_gain * u for the current input; the part
+        // the sensor has not shown yet is still to come. adjusted_pv already
+        // covers the dead time, so the rest follows smith_tau from there.
+        let pending = tunables.smith_gain * current_control_effort - y_pred_delayed;
+        let cpu_steady_state = cpu_temp + pending;
+        let cpu_time_to_limit = if cpu_temp >= tunables.hard_limit_cpu {
+            Some(0.0)
+        } else {
+            project_first_order(
+                adjusted_pv,
+                cpu_steady_state,
+                tunables.smith_tau,
+                tunables.hard_limit_cpu,
+            )
+        };
+        // The battery sees the same pending rise through its slower time constant.
+        let bat_steady_state = bat_temp + pending;
+        let bat_time_to_limit = project_first_order(
+            bat_temp,
+            bat_steady_state,
+            tunables.bat_thermal_tau,
+            tunables.hard_limit_bat,
+        );
+        self.forecast = ThermalForecast {
+            cpu_steady_state,
+            bat_steady_state,
+            cpu_time_to_limit,
+            bat_time_to_limit,
+        };
         let error = adjusted_pv - setpoint;
         let p_term = k_p * error;
         let i_increment = k_i * error * dt_safe;
@@ -0,0 +1,336 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::algorithms::thermal_math;
+
+use std::{collections, f32::consts, time};
+
+const SAMPLE_INTERVAL_SEC: f32 = 1.0;
+const BASELINE_WINDOW: usize = 10;
+const SETTLE_WINDOW: usize = 10;
+const MAX_STEP_WINDOW_SEC: f32 = 240.0;
+const BASELINE_TEMP_SPAN: f32 = 0.6;
+const BASELINE_UTIL_SPAN: f32 = 0.15;
+const SETTLE_TEMP_SPAN: f32 = 0.4;
+const STEP_MIN: f32 = 0.3;
+const STEP_BAND: f32 = 0.15;
+const MIN_TEMP_RISE: f32 = 2.0;
+const UNCONSTRAINED_SCALE: f32 = 0.95;
+const REQUIRED_RUNS: usize = 3;
+const MIN_GAIN_MARGIN: f32 = 2.0;
+const MIN_TAU_SEC: f32 = 2.0;
+const MAX_TAU_SEC: f32 = 180.0;
+const MAX_DEAD_TIME_SEC: f32 = 15.0;
+const GAIN_BOUND_RATIO: f32 = 3.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct PlantModel {
+    pub gain: f32,
+    pub tau: f32,
+    pub dead_time: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TunedGains {
+    pub kp_base: f32,
+    pub ki_base: f32,
+    pub kd_fast: f32,
+    pub smith_tau: f32,
+    pub smith_delay_sec: f32,
+}
+
+impl TunedGains {
+    pub fn apply(&self, config: &mut thermal_math::ThermalConfig) {
+        config.kp_base = self.kp_base;
+        config.ki_base = self.ki_base;
+        config.kd_fast = self.kd_fast;
+        config.smith_tau = self.smith_tau;
+        config.smith_delay_sec = self.smith_delay_sec;
+    }
+    pub fn is_plausible(&self, defaults: &thermal_math::ThermalConfig) -> bool {
+        within_bounds(self.kp_base, defaults.kp_base)
+            && within_bounds(self.ki_base, defaults.ki_base)
+            && within_bounds(self.kd_fast, defaults.kd_fast)
+            && (MIN_TAU_SEC..=MAX_TAU_SEC).contains(&self.smith_tau)
+            && (0.0..=MAX_DEAD_TIME_SEC).contains(&self.smith_delay_sec)
+    }
+}
+
+#[inline]
+fn within_bounds(value: f32, reference: f32) -> bool {
+    value.is_finite()
+        && value >= reference / GAIN_BOUND_RATIO
+        && value <= reference * GAIN_BOUND_RATIO
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StepSample {
+    pub elapsed: f32,
+    pub temp: f32,
+}
+
+pub fn identify_step_response(
+    samples: &[StepSample],
+    start_temp: f32,
+    input_step: f32,
+) -> Option<PlantModel> {
+    if samples.len() < SETTLE_WINDOW * 2 || input_step <= 0.0 {
+        return None;
+    }
+    let tail = &samples[samples.len() - SETTLE_WINDOW..];
+    let final_temp = tail.iter().map(|s| s.temp).sum::<f32>() / tail.len() as f32;
+    let rise = final_temp - start_temp;
+    if rise < MIN_TEMP_RISE {
+        return None;
+    }
+    let crossing = |fraction: f32| {
+        let level = start_temp + fraction * rise;
+        let i = samples.iter().position(|s| s.temp >= level)?;
+        let prev = i.checked_sub(1).map_or(
+            StepSample {
+                elapsed: 0.0,
+                temp: start_temp,
+            },
+            |p| samples[p],
+        );
+        let next = samples[i];
+        let span = next.temp - prev.temp;
+        let fraction = if span > f32::EPSILON {
+            ((level - prev.temp) / span).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        Some(prev.elapsed + fraction * (next.elapsed - prev.elapsed))
+    };
+    let t28 = crossing(0.283)?;
+    let t63 = crossing(0.632)?;
+    let tau = 1.5 * (t63 - t28);
+    let dead_time = (t63 - tau).max(0.0);
+    if !(MIN_TAU_SEC..=MAX_TAU_SEC).contains(&tau) || dead_time > MAX_DEAD_TIME_SEC {
+        return None;
+    }
+    Some(PlantModel {
+        gain: rise / input_step,
+        tau,
+        dead_time,
+    })
+}
+
+fn gain_margin(kp: f32, ki: f32, kd: f32, model: &PlantModel) -> f32 {
+    let mut omega = 1e-3_f32;
+    while omega < 20.0 {
+        let controller_imag = kd * omega - ki / omega;
+        let phase =
+            controller_imag.atan2(kp) - model.dead_time * omega - (model.tau * omega).atan();
+        if phase <= -consts::PI {
+            let controller_mag = kp.hypot(controller_imag);
+            let plant_mag = model.gain / (model.tau * omega).hypot(1.0);
+            let loop_mag = controller_mag * plant_mag;
+            return if loop_mag > 0.0 {
+                1.0 / loop_mag
+            } else {
+                f32::INFINITY
+            };
+        }
+        omega *= 1.05;
+    }
+    f32::INFINITY
+}
+
+pub fn derive_gains(
+    model: &PlantModel,
+    defaults: &thermal_math::ThermalConfig,
+) -> Option<TunedGains> {
+    let effort_gain = model.gain / 100.0;
+    if effort_gain <= 0.0 {
+        return None;
+    }
+    let closed_loop_tau = model.dead_time.max(1.0);
+    let kc = model.tau / (effort_gain * (closed_loop_tau + model.dead_time));
+    let ti = model.tau.min(4.0 * (closed_loop_tau + model.dead_time));
+    let td = model.dead_time * 0.5;
+    let fast_ratio = defaults.kp_fast / defaults.kp_base;
+    let bound = |value: f32, reference: f32| {
+        value.clamp(reference / GAIN_BOUND_RATIO, reference * GAIN_BOUND_RATIO)
+    };
+    let kp_base = bound(kc, defaults.kp_base);
+    let ki_base = bound(kc / ti, defaults.ki_base);
+    let kd_fast = bound(kc * fast_ratio * td, defaults.kd_fast);
+    let kp_fast = kp_base * fast_ratio;
+    let ki_fast = ki_base * defaults.ki_fast / defaults.ki_base;
+    if gain_margin(
+        kp_fast * effort_gain,
+        ki_fast * effort_gain,
+        kd_fast * effort_gain,
+        &PlantModel {
+            gain: 1.0,
+            ..*model
+        },
+    ) < MIN_GAIN_MARGIN
+    {
+        return None;
+    }
+    Some(TunedGains {
+        kp_base,
+        ki_base,
+        kd_fast,
+        smith_tau: model.tau,
+        smith_delay_sec: model.dead_time,
+    })
+}
+
+fn median(values: &mut [f32]) -> f32 {
+    values.sort_by(f32::total_cmp);
+    values[values.len() / 2]
+}
+
+enum Phase {
+    Baseline,
+    Step {
+        level: f32,
+        baseline_util: f32,
+        start_temp: f32,
+        started: time::Instant,
+        samples: Vec<StepSample>,
+    },
+}
+
+pub struct ThermalTuner {
+    defaults: thermal_math::ThermalConfig,
+    phase: Phase,
+    baseline: collections::VecDeque<(f32, f32)>,
+    models: Vec<PlantModel>,
+    last_sample: time::Instant,
+    util_sum: f32,
+    temp_sum: f32,
+    count: u32,
+    completed: bool,
+}
+
+impl ThermalTuner {
+    pub fn new(defaults: thermal_math::ThermalConfig) -> Self {
+        Self {
+            defaults,
+            phase: Phase::Baseline,
+            baseline: collections::VecDeque::with_capacity(BASELINE_WINDOW),
+            models: Vec::with_capacity(REQUIRED_RUNS),
+            last_sample: time::Instant::now(),
+            util_sum: 0.0,
+            temp_sum: 0.0,
+            count: 0,
+            completed: false,
+        }
+    }
+    pub fn observe(
+        &mut self,
+        now: time::Instant,
+        utilization: f32,
+        temp: f32,
+        thermal_scale: f32,
+    ) -> Option<TunedGains> {
+        if self.completed {
+            return None;
+        }
+        self.util_sum += utilization;
+        self.temp_sum += temp;
+        self.count += 1;
+        if now.duration_since(self.last_sample).as_secs_f32() < SAMPLE_INTERVAL_SEC {
+            return None;
+        }
+        let util = self.util_sum / self.count as f32;
+        let temp = self.temp_sum / self.count as f32;
+        self.util_sum = 0.0;
+        self.temp_sum = 0.0;
+        self.count = 0;
+        self.last_sample = now;
+        if thermal_scale < UNCONSTRAINED_SCALE {
+            self.reset();
+            return None;
+        }
+        let model = match &mut self.phase {
+            Phase::Baseline => {
+                self.sample_baseline(util, temp, now);
+                None
+            }
+            Phase::Step {
+                level,
+                baseline_util,
+                start_temp,
+                started,
+                samples,
+            } => {
+                let elapsed = now.duration_since(*started).as_secs_f32();
+                if (util - *level).abs() > STEP_BAND || elapsed > MAX_STEP_WINDOW_SEC {
+                    self.reset();
+                    return None;
+                }
+                samples.push(StepSample { elapsed, temp });
+                if samples.len() < SETTLE_WINDOW * 2 {
+                    return None;
+                }
+                let tail = &samples[samples.len() - SETTLE_WINDOW..];
+                let (lo, hi) = span(tail.iter().map(|s| s.temp));
+                if hi - lo > SETTLE_TEMP_SPAN {
+                    return None;
+                }
+                let model = identify_step_response(samples, *start_temp, *level - *baseline_util);
+                self.reset();
+                model
+            }
+        };
+        let model = model?;
+        log::debug!(
+            "ThermalTuner: Identified K={:.2} tau={:.1}s theta={:.1}s",
+            model.gain,
+            model.tau,
+            model.dead_time
+        );
+        self.models.push(model);
+        if self.models.len() < REQUIRED_RUNS {
+            return None;
+        }
+        let mut gains: Vec<f32> = self.models.iter().map(|m| m.gain).collect();
+        let mut taus: Vec<f32> = self.models.iter().map(|m| m.tau).collect();
+        let mut delays: Vec<f32> = self.models.iter().map(|m| m.dead_time).collect();
+        self.models.clear();
+        let consensus = PlantModel {
+            gain: median(&mut gains),
+            tau: median(&mut taus),
+            dead_time: median(&mut delays),
+        };
+        let tuned = derive_gains(&consensus, &self.defaults)?;
+        self.completed = true;
+        Some(tuned)
+    }
+    fn sample_baseline(&mut self, util: f32, temp: f32, now: time::Instant) {
+        if self.baseline.len() == BASELINE_WINDOW {
+            let (util_lo, util_hi) = span(self.baseline.iter().map(|s| s.0));
+            let (temp_lo, temp_hi) = span(self.baseline.iter().map(|s| s.1));
+            let baseline_util = (util_lo + util_hi) * 0.5;
+            let steady =
+                util_hi - util_lo <= BASELINE_UTIL_SPAN && temp_hi - temp_lo <= BASELINE_TEMP_SPAN;
+            if steady && util >= baseline_util + STEP_MIN {
+                let start_temp =
+                    self.baseline.iter().map(|s| s.1).sum::<f32>() / BASELINE_WINDOW as f32;
+                self.baseline.clear();
+                self.phase = Phase::Step {
+                    level: util,
+                    baseline_util,
+                    start_temp,
+                    started: now,
+                    samples: Vec::with_capacity(MAX_STEP_WINDOW_SEC as usize),
+                };
+                return;
+            }
+            self.baseline.pop_front();
+        }
+        self.baseline.push_back((util, temp));
+    }
+    fn reset(&mut self) {
+        self.phase = Phase::Baseline;
+        self.baseline.clear();
+    }
+}
+
+fn span(values: impl Iterator<Item = f32>) -> (f32, f32) {
+    values.fold((f32::MAX, f32::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)))
+}
@@ -0,0 +1,124 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::resources::discovery;
+use crate::utils::tier::DeviceTier;
+
+const SWAPPINESS_LIMIT: f32 = 200.0;
+const LEGACY_SWAPPINESS_LIMIT: f32 = 100.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ZramMathConfig {
+    pub min_swappiness: f32,
+    pub max_swappiness: f32,
+    pub mem_psi_high: f32,
+    pub fill_soft: f32,
+    pub fill_hard: f32,
+    pub recompress_fill: f32,
+    pub writeback_fill: f32,
+    pub idle_age_sec: f32,
+}
+
+impl ZramMathConfig {
+    fn with_swappiness_limit(mut self, limit: f32) -> Self {
+        let scale = limit / SWAPPINESS_LIMIT;
+        self.min_swappiness *= scale;
+        self.max_swappiness *= scale;
+        self
+    }
+}
+
+impl Default for ZramMathConfig {
+    fn default() -> Self {
+        let tier = DeviceTier::get();
+        let cfg = match tier {
+            DeviceTier::Flagship => Self {
+                min_swappiness: 60.0,
+                max_swappiness: 150.0,
+                mem_psi_high: 10.0,
+                fill_soft: 0.70,
+                fill_hard: 0.92,
+                recompress_fill: 0.50,
+                writeback_fill: 0.80,
+                idle_age_sec: 1800.0,
+            },
+            DeviceTier::MidRange => Self {
+                min_swappiness: 80.0,
+                max_swappiness: 170.0,
+                mem_psi_high: 8.0,
+                fill_soft: 0.70,
+                fill_hard: 0.92,
+                recompress_fill: 0.45,
+                writeback_fill: 0.75,
+                idle_age_sec: 1200.0,
+            },
+            DeviceTier::LowEnd => Self {
+                min_swappiness: 100.0,
+                max_swappiness: 200.0,
+                mem_psi_high: 6.0,
+                fill_soft: 0.65,
+                fill_hard: 0.90,
+                recompress_fill: 0.40,
+                writeback_fill: 0.70,
+                idle_age_sec: 900.0,
+            },
+        };
+        // Kernels before 5.8 reject swappiness above 100.
+        if discovery::kernel_version() < (5, 8) {
+            cfg.with_swappiness_limit(LEGACY_SWAPPINESS_LIMIT)
+        } else {
+            cfg
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZramAction {
+    Idle,
+    Recompress,
+    Writeback,
+}
+
+impl ZramAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ZramAction::Idle => "idle",
+            ZramAction::Recompress => "recompress",
+            ZramAction::Writeback => "writeback",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ZramCapabilities {
+    pub recompress: bool,
+    pub writeback: bool,
+}
+
+#[inline]
+pub fn calculate_swappiness(mem_psi: f32, fill: f32, cfg: &ZramMathConfig) -> f32 {
+    let urgency = (mem_psi / cfg.mem_psi_high.max(0.1)).clamp(0.0, 1.0);
+    let span = (cfg.fill_hard - cfg.fill_soft).max(0.01);
+    let headroom = 1.0 - ((fill - cfg.fill_soft) / span).clamp(0.0, 1.0);
+    let level = (0.5 + 0.5 * urgency) * headroom;
+    cfg.min_swappiness + level * (cfg.max_swappiness - cfg.min_swappiness)
+}
+
+pub fn decide_maintenance(
+    fill: f32,
+    mem_psi: f32,
+    idle_age_sec: f32,
+    caps: ZramCapabilities,
+    cfg: &ZramMathConfig,
+) -> ZramAction {
+    if idle_age_sec < cfg.idle_age_sec {
+        return ZramAction::Idle;
+    }
+    let pressured = mem_psi >= cfg.mem_psi_high;
+    if caps.writeback && (fill >= cfg.writeback_fill || pressured) {
+        ZramAction::Writeback
+    } else if caps.recompress && fill >= cfg.recompress_fill && !pressured {
+        ZramAction::Recompress
+    } else {
+        ZramAction::Idle
+    }
+}
@@ -1,6 +1,9 @@
 //! Author: [Seclususs](https://github.com/seclususs)
 
-use crate::controllers::{blocker_impl, cleaner_impl, cpu_impl, signal_impl, storage_impl};
+use crate::controllers::{
+    blocker_impl, charging_impl, cleaner_impl, cpu_impl, cpuset_impl, f2fs_impl, signal_impl,
+    status_impl, storage_impl, trim_impl, zram_impl,
+};
 use crate::daemon::{logging, runtime, state};
 use crate::hal::bridge;
 
@@ -13,6 +16,11 @@ pub extern "C" fn rust_set_blocker_service_enabled(enabled: bool) {
     state::BLOCKER_SERVICE_ENABLED.store(enabled, sync::atomic::Ordering::Release);
 }
 
+#[unsafe(no_mangle)]
+pub extern "C" fn rust_set_charging_service_enabled(enabled: bool) {
+    state::CHARGING_SERVICE_ENABLED.store(enabled, sync::atomic::Ordering::Release);
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn rust_set_cleaner_service_enabled(enabled: bool) {
     state::CLEANER_SERVICE_ENABLED.store(enabled, sync::atomic::Ordering::Release);
@@ -23,16 +31,95 @@ pub extern "C" fn rust_set_cpu_service_enabled(enabled: bool) {
     state::CPU_SERVICE_ENABLED.store(enabled, sync::atomic::Ordering::Release);
 }
 
+#[unsafe(no_mangle)]
+pub extern "C" fn rust_set_cpuset_service_enabled(enabled: bool) {
+    state::CPUSET_SERVICE_ENABLED.store(enabled, sync::atomic::Ordering::Release);
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn rust_set_f2fs_service_enabled(enabled: bool) {
+    state::F2FS_SERVICE_ENABLED.store(enabled, sync::atomic::Ordering::Release);
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn rust_set_storage_service_enabled(enabled: bool) {
     state::STORAGE_SERVICE_ENABLED.store(enabled, sync::atomic::Ordering::Release);
 }
 
+#[unsafe(no_mangle)]
+pub extern "C" fn rust_set_trim_service_enabled(enabled: bool) {
+    state::TRIM_SERVICE_ENABLED.store(enabled, sync::atomic::Ordering::Release);
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn rust_set_tweaks_enabled(enabled: bool) {
     state::TWEAKS_ENABLED.store(enabled, sync::atomic::Ordering::Release);
 }
 
+#[unsafe(no_mangle)]
+pub extern "C" fn rust_set_zram_service_enabled(enabled: bool) {
+    state::ZRAM_SERVICE_ENABLED.store(enabled, sync::atomic::Ordering::Release);
+}
+
+fn build_services(signal_fd: i32) -> Vec<runtime::RecoverableService> {
+    log::info!("Rust: Constructing Service Vector...");
+    let mut services = Vec::new();
+    services.push(runtime::RecoverableService::new("Signal", move || {
+        Ok(Box::new(unsafe {
+            signal_impl::SignalController::new(signal_fd)
+        }))
+    }));
+    if state::STORAGE_SERVICE_ENABLED.load(sync::atomic::Ordering::Acquire) {
+        services.push(runtime::RecoverableService::new("Storage", || {
+            Ok(Box::new(storage_impl::StorageController::new()?))
+        }));
+    }
+    if state::F2FS_SERVICE_ENABLED.load(sync::atomic::Ordering::Acquire) {
+        services.push(runtime::RecoverableService::new("F2fs", || {
+            Ok(Box::new(f2fs_impl::F2fsController::new()?))
+        }));
+    }
+    if state::TRIM_SERVICE_ENABLED.load(sync::atomic::Ordering::Acquire) {
+        services.push(runtime::RecoverableService::new("Trim", || {
+            Ok(Box::new(trim_impl::TrimController::new()?))
+        }));
+    }
+    if state::ZRAM_SERVICE_ENABLED.load(sync::atomic::Ordering::Acquire) {
+        services.push(runtime::RecoverableService::new("Zram", || {
+            Ok(Box::new(zram_impl::ZramController::new()?))
+        }));
+    }
+    if state::CPU_SERVICE_ENABLED.load(sync::atomic::Ordering::Acquire) {
+        services.push(runtime::RecoverableService::new("CPU", || {
+            Ok(Box::new(cpu_impl::CpuController::new()?))
+        }));
+    }
+    if state::CPUSET_SERVICE_ENABLED.load(sync::atomic::Ordering::Acquire) {
+        services.push(runtime::RecoverableService::new("Cpuset", || {
+            Ok(Box::new(cpuset_impl::CpusetController::new()?))
+        }));
+    }
+    if state::CHARGING_SERVICE_ENABLED.load(sync::atomic::Ordering::Acquire) {
+        services.push(runtime::RecoverableService::new("Charging", || {
+            Ok(Box::new(charging_impl::ChargingController::new()?))
+        }));
+    }
+    if state::CLEANER_SERVICE_ENABLED.load(sync::atomic::Ordering::Acquire) {
+        services.push(runtime::RecoverableService::new("Cleaner", || {
+            Ok(Box::new(cleaner_impl::CleanerController::new()?))
+        }));
+    }
+    if state::BLOCKER_SERVICE_ENABLED.load(sync::atomic::Ordering::Acquire) {
+        services.push(runtime::RecoverableService::new("Blocker", || {
+            Ok(Box::new(blocker_impl::BlockerController::new()?))
+        }));
+    }
+    services.push(runtime::RecoverableService::new("Status", || {
+        Ok(Box::new(status_impl::StatusController::new()?))
+    }));
+    services
+}
+
 /// # Safety
 /// Initializes the Rust runtime and starts background services.
 /// # Requirements
@@ -88,33 +175,7 @@ pub unsafe extern "C" fn rust_start_services(signal_fd: i32) -> i32 {
                 if state::SHUTDOWN_REQUESTED.load(sync::atomic::Ordering::Acquire) {
                     return;
                 }
-                log::info!("Rust: Constructing Service Vector...");
-                let mut services = Vec::new();
-                services.push(runtime::RecoverableService::new("Signal", move || {
-                    Ok(Box::new(unsafe {
-                        signal_impl::SignalController::new(signal_fd)
-                    }))
-                }));
-                if state::STORAGE_SERVICE_ENABLED.load(sync::atomic::Ordering::Acquire) {
-                    services.push(runtime::RecoverableService::new("Storage", || {
-                        Ok(Box::new(storage_impl::StorageController::new()?))
-                    }));
-                }
-                if state::CPU_SERVICE_ENABLED.load(sync::atomic::Ordering::Acquire) {
-                    services.push(runtime::RecoverableService::new("CPU", || {
-                        Ok(Box::new(cpu_impl::CpuController::new()?))
-                    }));
-                }
-                if state::CLEANER_SERVICE_ENABLED.load(sync::atomic::Ordering::Acquire) {
-                    services.push(runtime::RecoverableService::new("Cleaner", || {
-                        Ok(Box::new(cleaner_impl::CleanerController::new()?))
-                    }));
-                }
-                if state::BLOCKER_SERVICE_ENABLED.load(sync::atomic::Ordering::Acquire) {
-                    services.push(runtime::RecoverableService::new("Blocker", || {
-                        Ok(Box::new(blocker_impl::BlockerController::new()?))
-                    }));
-                }
+                let services = build_services(signal_fd);
                 log::info!(
                     "Rust: Initializing Event Loop with {} services...",
                     services.len()
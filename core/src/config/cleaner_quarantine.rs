@@ -0,0 +1,46 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::daemon::types;
+use crate::hal::filesystem;
+use crate::resources::sys_paths;
+
+use std::fmt::Write;
+use std::{collections, fs};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QuarantineEntry {
+    pub first_seen: u64,
+    pub bytes: u64,
+}
+
+pub fn load() -> collections::BTreeMap<String, QuarantineEntry> {
+    let Ok(content) = fs::read_to_string(sys_paths::K_CLEANER_QUARANTINE_PATH) else {
+        return collections::BTreeMap::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            if key.trim() != "orphan" {
+                return None;
+            }
+            let (first_seen, rest) = value.trim().split_once(' ')?;
+            let (bytes, path) = rest.split_once(' ')?;
+            let entry = QuarantineEntry {
+                first_seen: first_seen.parse().ok()?,
+                bytes: bytes.parse().ok()?,
+            };
+            Some((path.to_string(), entry))
+        })
+        .collect()
+}
+
+pub fn save(
+    entries: &collections::BTreeMap<String, QuarantineEntry>,
+) -> Result<(), types::QosError> {
+    let mut body = String::with_capacity(96 * entries.len());
+    for (path, entry) in entries {
+        let _ = writeln!(body, "orphan={} {} {path}", entry.first_seen, entry.bytes);
+    }
+    filesystem::write_state_file(sys_paths::K_CLEANER_QUARANTINE_PATH, &body)
+}
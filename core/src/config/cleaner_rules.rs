@@ -0,0 +1,518 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::hal::traversal;
+use crate::resources::sys_paths;
+
+use std::{fs, time};
+
+const BUILTIN_RULES: &str = include_str!("../../../magisk-module/cleaner_rules.conf");
+const MIN_AGE: time::Duration = time::Duration::from_mins(1);
+const ALLOWED_ROOTS: &[&str] = &["/data/", "/sdcard/", "/storage/emulated/"];
+const DENIED_ROOTS: &[&str] = &[
+    "/data/adb",
+    "/data/app",
+    "/data/misc",
+    "/data/system",
+    "/data/vendor",
+];
+const USER_ROOTS: &[(&str, &str)] = &[
+    ("/data/data/", "/data/user/"),
+    ("/data/user/0/", "/data/user/"),
+    ("/data/user_de/0/", "/data/user_de/"),
+    ("/data/media/0/", "/data/media/"),
+    ("/sdcard/", "/data/media/"),
+    ("/storage/emulated/0/", "/data/media/"),
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CleanMode {
+    #[default]
+    Age,
+    Quota,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ActiveApps {
+    #[default]
+    OpenFiles,
+    Skip,
+    Ignore,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CleanerRule {
+    pub name: String,
+    pub mode: CleanMode,
+    pub paths: Vec<String>,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub trash: Vec<String>,
+    pub max_age: Option<time::Duration>,
+    pub trash_age: Option<time::Duration>,
+    pub bloat_size: Option<u64>,
+    pub bloat_age: Option<time::Duration>,
+    pub app_quota: Option<u64>,
+    pub total_quota: Option<u64>,
+    pub active_apps: ActiveApps,
+    pub emergency_age: Option<time::Duration>,
+    pub emergency_keep_excluded: bool,
+    pub emergency_only: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PackageOverride {
+    pub pattern: String,
+    pub rules: Vec<String>,
+    pub skip: bool,
+    pub max_age: Option<time::Duration>,
+    pub trash_age: Option<time::Duration>,
+    pub bloat_size: Option<u64>,
+    pub bloat_age: Option<time::Duration>,
+    pub app_quota: Option<u64>,
+    pub active_apps: Option<ActiveApps>,
+    pub emergency_age: Option<time::Duration>,
+    pub exclude: Vec<String>,
+}
+
+impl PackageOverride {
+    fn applies(&self, rule: &str, package: &str) -> bool {
+        (self.rules.is_empty() || self.rules.iter().any(|r| r == rule))
+            && traversal::glob_match(self.pattern.as_bytes(), package.as_bytes())
+    }
+    fn is_noop(&self) -> bool {
+        !self.skip
+            && self.max_age.is_none()
+            && self.trash_age.is_none()
+            && self.bloat_size.is_none()
+            && self.bloat_age.is_none()
+            && self.app_quota.is_none()
+            && self.active_apps.is_none()
+            && self.emergency_age.is_none()
+            && self.exclude.is_empty()
+    }
+}
+
+pub struct RulePolicy<'a> {
+    pub rule: &'a CleanerRule,
+    pub max_age: Option<time::Duration>,
+    pub trash_age: Option<time::Duration>,
+    pub bloat_size: Option<u64>,
+    pub bloat_age: Option<time::Duration>,
+    pub app_quota: Option<u64>,
+    pub active_apps: ActiveApps,
+    pub emergency_age: Option<time::Duration>,
+    extra_exclude: Vec<&'a str>,
+}
+
+fn matches_any<'a>(patterns: impl IntoIterator<Item = &'a str>, name: &[u8]) -> bool {
+    patterns
+        .into_iter()
+        .any(|p| traversal::glob_match(p.as_bytes(), name))
+}
+
+impl RulePolicy<'_> {
+    pub fn is_excluded(&self, name: &[u8], emergency: bool) -> bool {
+        if emergency && !self.rule.emergency_keep_excluded {
+            return false;
+        }
+        matches_any(self.rule.exclude.iter().map(String::as_str), name)
+            || matches_any(self.extra_exclude.iter().copied(), name)
+    }
+    pub fn is_included(&self, name: &[u8]) -> bool {
+        self.rule.include.is_empty()
+            || matches_any(self.rule.include.iter().map(String::as_str), name)
+    }
+    pub fn is_trash(&self, name: &[u8]) -> bool {
+        matches_any(self.rule.trash.iter().map(String::as_str), name)
+    }
+}
+
+pub fn user_path(path: &str, user: u32) -> Option<String> {
+    if user == 0 {
+        return Some(path.to_string());
+    }
+    USER_ROOTS.iter().find_map(|(root, base)| {
+        path.strip_prefix(root)
+            .map(|rest| format!("{base}{user}/{rest}"))
+    })
+}
+
+pub struct CleanerRules {
+    pub rules: Vec<CleanerRule>,
+    pub overrides: Vec<PackageOverride>,
+    pub source: &'static str,
+}
+
+impl CleanerRules {
+    pub fn resolve<'a>(
+        &'a self,
+        rule: &'a CleanerRule,
+        package: Option<&str>,
+    ) -> Option<RulePolicy<'a>> {
+        let mut policy = RulePolicy {
+            rule,
+            max_age: rule.max_age,
+            trash_age: rule.trash_age.or(rule.max_age),
+            bloat_size: rule.bloat_size,
+            bloat_age: rule.bloat_age.or(rule.max_age),
+            app_quota: rule.app_quota,
+            active_apps: rule.active_apps,
+            emergency_age: rule.emergency_age,
+            extra_exclude: Vec::new(),
+        };
+        let Some(package) = package else {
+            return Some(policy);
+        };
+        for ov in self
+            .overrides
+            .iter()
+            .filter(|ov| ov.applies(&rule.name, package))
+        {
+            if ov.skip {
+                return None;
+            }
+            policy.max_age = ov.max_age.or(policy.max_age);
+            policy.trash_age = ov.trash_age.or(policy.trash_age);
+            policy.bloat_size = ov.bloat_size.or(policy.bloat_size);
+            policy.bloat_age = ov.bloat_age.or(policy.bloat_age);
+            policy.app_quota = ov.app_quota.or(policy.app_quota);
+            policy.active_apps = ov.active_apps.unwrap_or(policy.active_apps);
+            policy.emergency_age = ov.emergency_age.or(policy.emergency_age);
+            policy
+                .extra_exclude
+                .extend(ov.exclude.iter().map(String::as_str));
+        }
+        Some(policy)
+    }
+}
+
+fn parse_duration(value: &str) -> Result<time::Duration, String> {
+    if !value.is_ascii() {
+        return Err(format!("invalid duration '{value}'"));
+    }
+    let split = value.len().saturating_sub(1);
+    let (number, unit) = value.split_at(split);
+    let scale = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        _ => return Err(format!("duration '{value}' needs a s/m/h/d suffix")),
+    };
+    let secs: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration '{value}'"))?;
+    let duration = time::Duration::from_secs(secs.saturating_mul(scale));
+    if duration < MIN_AGE {
+        return Err(format!("duration '{value}' is below the 1m minimum"));
+    }
+    Ok(duration)
+}
+
+fn parse_size(value: &str) -> Result<u64, String> {
+    let (number, scale) = match value.as_bytes().last() {
+        Some(b'K' | b'k') => (&value[..value.len() - 1], 1u64 << 10),
+        Some(b'M' | b'm') => (&value[..value.len() - 1], 1 << 20),
+        Some(b'G' | b'g') => (&value[..value.len() - 1], 1 << 30),
+        _ => (value, 1),
+    };
+    let size: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid size '{value}'"))?;
+    match size.saturating_mul(scale) {
+        0 => Err("size must be positive".to_string()),
+        bytes => Ok(bytes),
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value {
+        "true" | "1" | "True" => Ok(true),
+        "false" | "0" | "False" => Ok(false),
+        _ => Err(format!("invalid boolean '{value}'")),
+    }
+}
+
+fn parse_patterns(value: &str, out: &mut Vec<String>) -> Result<(), String> {
+    for pattern in value.split(',').map(str::trim) {
+        if pattern.is_empty() || pattern.contains('/') {
+            return Err(format!("invalid name pattern '{pattern}'"));
+        }
+        out.push(pattern.to_string());
+    }
+    Ok(())
+}
+
+// Segments naming the storage root itself, which a wildcard must not stand in for.
+fn storage_root_depth(segments: &[&str]) -> usize {
+    match segments {
+        ["storage", "emulated", ..] | ["data", "media", ..] => 3,
+        _ => 1,
+    }
+}
+
+fn validate_path(value: &str) -> Result<String, String> {
+    if !ALLOWED_ROOTS.iter().any(|root| value.starts_with(root)) {
+        return Err(format!("path '{value}' is outside the allowed roots"));
+    }
+    let segments: Vec<&str> = value.split('/').filter(|s| !s.is_empty()).collect();
+    if segments.iter().any(|&s| s == ".." || s == ".") {
+        return Err(format!("path '{value}' contains relative segments"));
+    }
+    if segments.len() < 2 {
+        return Err(format!("path '{value}' is too broad"));
+    }
+    let is_wild = |s: &&str| s.contains(['*', '?']);
+    if let (Some(first), Some(last)) = (
+        segments.iter().position(is_wild),
+        segments.iter().rposition(is_wild),
+    ) && (first <= storage_root_depth(&segments) || last + 1 == segments.len())
+    {
+        return Err(format!(
+            "path '{value}' needs literal directories around its wildcards"
+        ));
+    }
+    let literal = value.split(['*', '?']).next().unwrap_or(value);
+    if DENIED_ROOTS.iter().any(|denied| {
+        literal.starts_with(denied) || denied.starts_with(literal.trim_end_matches('/'))
+    }) {
+        return Err(format!("path '{value}' touches a protected directory"));
+    }
+    Ok(value.trim_end_matches('/').to_string())
+}
+
+enum Section {
+    Rule(CleanerRule),
+    Package(PackageOverride),
+}
+
+fn parse_mode(value: &str) -> Result<CleanMode, String> {
+    match value {
+        "age" => Ok(CleanMode::Age),
+        "quota" => Ok(CleanMode::Quota),
+        _ => Err(format!("invalid mode '{value}'")),
+    }
+}
+
+fn parse_active_apps(value: &str) -> Result<ActiveApps, String> {
+    match value {
+        "open_files" => Ok(ActiveApps::OpenFiles),
+        "skip" => Ok(ActiveApps::Skip),
+        "ignore" => Ok(ActiveApps::Ignore),
+        _ => Err(format!("invalid active_apps '{value}'")),
+    }
+}
+
+fn apply_rule_key(rule: &mut CleanerRule, key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "mode" => rule.mode = parse_mode(value)?,
+        "path" => rule.paths.push(validate_path(value)?),
+        "include" => parse_patterns(value, &mut rule.include)?,
+        "exclude" => parse_patterns(value, &mut rule.exclude)?,
+        "trash" => parse_patterns(value, &mut rule.trash)?,
+        "max_age" => rule.max_age = Some(parse_duration(value)?),
+        "trash_age" => rule.trash_age = Some(parse_duration(value)?),
+        "bloat_size" => rule.bloat_size = Some(parse_size(value)?),
+        "bloat_age" => rule.bloat_age = Some(parse_duration(value)?),
+        "app_quota" => rule.app_quota = Some(parse_size(value)?),
+        "total_quota" => rule.total_quota = Some(parse_size(value)?),
+        "active_apps" => rule.active_apps = parse_active_apps(value)?,
+        "emergency_age" => rule.emergency_age = Some(parse_duration(value)?),
+        "emergency_keep_excluded" => rule.emergency_keep_excluded = parse_bool(value)?,
+        "emergency_only" => rule.emergency_only = parse_bool(value)?,
+        _ => return Err(format!("unknown rule key '{key}'")),
+    }
+    Ok(())
+}
+
+fn apply_package_key(ov: &mut PackageOverride, key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "rules" => ov
+            .rules
+            .extend(value.split(',').map(str::trim).map(str::to_string)),
+        "skip" => ov.skip = parse_bool(value)?,
+        "exclude" => parse_patterns(value, &mut ov.exclude)?,
+        "max_age" => ov.max_age = Some(parse_duration(value)?),
+        "trash_age" => ov.trash_age = Some(parse_duration(value)?),
+        "bloat_size" => ov.bloat_size = Some(parse_size(value)?),
+        "bloat_age" => ov.bloat_age = Some(parse_duration(value)?),
+        "app_quota" => ov.app_quota = Some(parse_size(value)?),
+        "active_apps" => ov.active_apps = Some(parse_active_apps(value)?),
+        "emergency_age" => ov.emergency_age = Some(parse_duration(value)?),
+        _ => return Err(format!("unknown package key '{key}'")),
+    }
+    Ok(())
+}
+
+fn validate_rule(rule: &CleanerRule, existing: &[CleanerRule]) -> Result<(), String> {
+    if existing.iter().any(|r| r.name == rule.name) {
+        return Err(format!("duplicate rule '{}'", rule.name));
+    }
+    if rule.paths.is_empty() {
+        return Err(format!("rule '{}' has no path", rule.name));
+    }
+    match rule.mode {
+        CleanMode::Age => {
+            if rule.max_age.is_none() {
+                return Err(format!("rule '{}' has no max_age", rule.name));
+            }
+            if rule.app_quota.is_some() || rule.total_quota.is_some() {
+                return Err(format!(
+                    "rule '{}' sets a quota without mode=quota",
+                    rule.name
+                ));
+            }
+        }
+        CleanMode::Quota => {
+            if rule.app_quota.is_none() && rule.total_quota.is_none() {
+                return Err(format!(
+                    "rule '{}' has no app_quota or total_quota",
+                    rule.name
+                ));
+            }
+            if rule.bloat_size.is_some() {
+                return Err(format!(
+                    "rule '{}' mixes bloat limits with quotas",
+                    rule.name
+                ));
+            }
+        }
+    }
+    if rule.bloat_size.is_some() != rule.bloat_age.is_some() {
+        return Err(format!(
+            "rule '{}' needs both bloat_size and bloat_age",
+            rule.name
+        ));
+    }
+    Ok(())
+}
+
+fn validate_override(ov: &PackageOverride, rules: &[CleanerRule]) -> Result<(), String> {
+    if let Some(unknown) = ov
+        .rules
+        .iter()
+        .find(|name| !rules.iter().any(|r| &r.name == *name))
+    {
+        return Err(format!(
+            "package '{}' references unknown rule '{unknown}'",
+            ov.pattern
+        ));
+    }
+    if ov.is_noop() {
+        return Err(format!("package '{}' overrides nothing", ov.pattern));
+    }
+    Ok(())
+}
+
+fn parse_header(header: &str) -> Result<Section, String> {
+    let (kind, name) = header
+        .split_once(char::is_whitespace)
+        .map(|(k, n)| (k, n.trim()))
+        .ok_or_else(|| format!("malformed section '[{header}]'"))?;
+    if name.is_empty() || name.contains(['/', ' ', '\t']) {
+        return Err(format!("invalid section name '{name}'"));
+    }
+    match kind {
+        "rule" => Ok(Section::Rule(CleanerRule {
+            name: name.to_string(),
+            emergency_keep_excluded: true,
+            ..CleanerRule::default()
+        })),
+        "package" => Ok(Section::Package(PackageOverride {
+            pattern: name.to_string(),
+            ..PackageOverride::default()
+        })),
+        _ => Err(format!("unknown section type '{kind}'")),
+    }
+}
+
+fn finish_section(
+    section: Option<(usize, Section)>,
+    rules: &mut Vec<CleanerRule>,
+    overrides: &mut Vec<(usize, PackageOverride)>,
+    source: &str,
+) {
+    match section {
+        Some((line, Section::Rule(rule))) => match validate_rule(&rule, rules) {
+            Ok(()) => rules.push(rule),
+            Err(e) => log::warn!("CleanerRules: line {line}: {e} ({source})."),
+        },
+        Some((line, Section::Package(ov))) => overrides.push((line, ov)),
+        None => {}
+    }
+}
+
+fn parse(content: &str, source: &'static str) -> CleanerRules {
+    let mut rules: Vec<CleanerRule> = Vec::new();
+    let mut overrides: Vec<(usize, PackageOverride)> = Vec::new();
+    let mut current: Option<(usize, Section)> = None;
+    for (index, raw) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            finish_section(current.take(), &mut rules, &mut overrides, source);
+            match parse_header(header.trim()) {
+                Ok(section) => current = Some((line_no, section)),
+                Err(e) => log::warn!("CleanerRules: line {line_no}: {e} ({source})."),
+            }
+            continue;
+        }
+        let Some((start, section)) = current.as_mut() else {
+            log::warn!("CleanerRules: line {line_no}: entry outside a section ({source}).");
+            continue;
+        };
+        let result = match line.split_once('=') {
+            None => Err(format!("expected key=value, got '{line}'")),
+            Some((key, value)) => match section {
+                Section::Rule(rule) => apply_rule_key(rule, key.trim(), value.trim()),
+                Section::Package(ov) => apply_package_key(ov, key.trim(), value.trim()),
+            },
+        };
+        if let Err(e) = result {
+            log::warn!(
+                "CleanerRules: line {line_no}: {e}; section at line {start} ignored ({source})."
+            );
+            current = None;
+        }
+    }
+    finish_section(current.take(), &mut rules, &mut overrides, source);
+    let overrides = overrides
+        .into_iter()
+        .filter_map(|(line, ov)| match validate_override(&ov, &rules) {
+            Ok(()) => Some(ov),
+            Err(e) => {
+                log::warn!("CleanerRules: line {line}: {e} ({source}).");
+                None
+            }
+        })
+        .collect();
+    CleanerRules {
+        rules,
+        overrides,
+        source,
+    }
+}
+
+pub fn load() -> CleanerRules {
+    match fs::read_to_string(sys_paths::K_CLEANER_RULES_PATH) {
+        Ok(content) => {
+            let loaded = parse(&content, "rules file");
+            if !loaded.rules.is_empty() {
+                log::info!(
+                    "CleanerRules: Loaded {} rules and {} package overrides.",
+                    loaded.rules.len(),
+                    loaded.overrides.len()
+                );
+                return loaded;
+            }
+            log::warn!("CleanerRules: No valid rules found, using built-in defaults.");
+        }
+        Err(e) => {
+            log::info!("CleanerRules: Rules file unavailable ({e}), using built-in defaults.");
+        }
+    }
+    parse(BUILTIN_RULES, "built-in")
+}
@@ -0,0 +1,58 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::daemon::types;
+use crate::hal::filesystem;
+use crate::resources::sys_paths;
+
+use std::fmt::Write;
+use std::fs;
+
+#[derive(Debug, Clone, Default)]
+pub struct CleanerTotals {
+    pub cycles: u64,
+    pub files: u64,
+    pub bytes: u64,
+    pub last_cycle: u64,
+    pub cursor: Option<(String, String)>,
+}
+
+pub fn load() -> CleanerTotals {
+    let mut totals = CleanerTotals::default();
+    let Ok(content) = fs::read_to_string(sys_paths::K_CLEANER_STATE_PATH) else {
+        return totals;
+    };
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        if key.trim() == "cursor" {
+            let value = value.trim();
+            let (rule, path) = value.split_once(' ').unwrap_or((value, ""));
+            totals.cursor = (!rule.is_empty()).then(|| (rule.to_string(), path.to_string()));
+            continue;
+        }
+        let Ok(parsed) = value.trim().parse::<u64>() else {
+            continue;
+        };
+        match key.trim() {
+            "cycles" => totals.cycles = parsed,
+            "files" => totals.files = parsed,
+            "bytes" => totals.bytes = parsed,
+            "last_cycle" => totals.last_cycle = parsed,
+            _ => {}
+        }
+    }
+    totals
+}
+
+pub fn save(totals: &CleanerTotals) -> Result<(), types::QosError> {
+    let mut body = String::with_capacity(96);
+    let _ = writeln!(body, "cycles={}", totals.cycles);
+    let _ = writeln!(body, "files={}", totals.files);
+    let _ = writeln!(body, "bytes={}", totals.bytes);
+    let _ = writeln!(body, "last_cycle={}", totals.last_cycle);
+    if let Some((rule, path)) = &totals.cursor {
+        let _ = writeln!(body, "cursor={rule} {path}");
+    }
+    filesystem::write_state_file(sys_paths::K_CLEANER_STATE_PATH, &body)
+}
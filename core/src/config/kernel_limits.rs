@@ -1,5 +1,6 @@
 //! Author: [Seclususs](https://github.com/seclususs)
 
+use crate::resources::discovery;
 use crate::utils::tier::DeviceTier;
 
 #[derive(Debug, Clone, Copy)]
@@ -16,6 +17,10 @@ pub struct CpuKernelLimitsConfig {
     pub max_walt_init_pct: u64,
     pub min_uclamp_min: u64,
     pub max_uclamp_min: u64,
+    pub min_bg_uclamp_max: u64,
+    pub max_bg_uclamp_max: u64,
+    pub min_base_slice_ns: u64,
+    pub max_base_slice_ns: u64,
 }
 
 impl Default for CpuKernelLimitsConfig {
@@ -35,6 +40,10 @@ impl Default for CpuKernelLimitsConfig {
                 max_walt_init_pct: 50,
                 min_uclamp_min: 0,
                 max_uclamp_min: 512,
+                min_bg_uclamp_max: 40,
+                max_bg_uclamp_max: 100,
+                min_base_slice_ns: 700_000,
+                max_base_slice_ns: 3_000_000,
             },
             DeviceTier::MidRange => Self {
                 min_latency_ns: 8_000_000,
@@ -49,6 +58,10 @@ impl Default for CpuKernelLimitsConfig {
                 max_walt_init_pct: 40,
                 min_uclamp_min: 0,
                 max_uclamp_min: 384,
+                min_bg_uclamp_max: 35,
+                max_bg_uclamp_max: 100,
+                min_base_slice_ns: 750_000,
+                max_base_slice_ns: 3_000_000,
             },
             DeviceTier::LowEnd => Self {
                 min_latency_ns: 9_000_000,
@@ -63,6 +76,10 @@ impl Default for CpuKernelLimitsConfig {
                 max_walt_init_pct: 38,
                 min_uclamp_min: 0,
                 max_uclamp_min: 320,
+                min_bg_uclamp_max: 30,
+                max_bg_uclamp_max: 100,
+                min_base_slice_ns: 1_000_000,
+                max_base_slice_ns: 3_000_000,
             },
         }
     }
@@ -74,6 +91,14 @@ pub struct StorageKernelLimitsConfig {
     pub max_read_ahead: u64,
     pub min_nr_requests: u64,
     pub max_nr_requests: u64,
+    pub min_dirty_ratio: u64,
+    pub max_dirty_ratio: u64,
+    pub min_dirty_background_ratio: u64,
+    pub max_dirty_background_ratio: u64,
+    pub min_dirty_expire_cs: u64,
+    pub max_dirty_expire_cs: u64,
+    pub min_dirty_writeback_cs: u64,
+    pub max_dirty_writeback_cs: u64,
 }
 
 impl Default for StorageKernelLimitsConfig {
@@ -85,18 +110,65 @@ impl Default for StorageKernelLimitsConfig {
                 max_read_ahead: 2048,
                 min_nr_requests: 64,
                 max_nr_requests: 384,
+                min_dirty_ratio: 10,
+                max_dirty_ratio: 40,
+                min_dirty_background_ratio: 3,
+                max_dirty_background_ratio: 15,
+                min_dirty_expire_cs: 1000,
+                max_dirty_expire_cs: 6000,
+                min_dirty_writeback_cs: 300,
+                max_dirty_writeback_cs: 3000,
             },
             DeviceTier::MidRange => Self {
                 min_read_ahead: 128,
                 max_read_ahead: 1024,
                 min_nr_requests: 64,
                 max_nr_requests: 256,
+                min_dirty_ratio: 10,
+                max_dirty_ratio: 30,
+                min_dirty_background_ratio: 3,
+                max_dirty_background_ratio: 10,
+                min_dirty_expire_cs: 1000,
+                max_dirty_expire_cs: 4500,
+                min_dirty_writeback_cs: 300,
+                max_dirty_writeback_cs: 2000,
             },
             DeviceTier::LowEnd => Self {
                 min_read_ahead: 128,
                 max_read_ahead: 768,
                 min_nr_requests: 64,
                 max_nr_requests: 192,
+                min_dirty_ratio: 8,
+                max_dirty_ratio: 20,
+                min_dirty_background_ratio: 2,
+                max_dirty_background_ratio: 8,
+                min_dirty_expire_cs: 1000,
+                max_dirty_expire_cs: 3000,
+                min_dirty_writeback_cs: 300,
+                max_dirty_writeback_cs: 1500,
+            },
+        }
+    }
+}
+
+impl StorageKernelLimitsConfig {
+    #[must_use]
+    pub fn for_class(self, class: discovery::StorageClass) -> Self {
+        match class {
+            discovery::StorageClass::Ufs => self,
+            discovery::StorageClass::Nvme => Self {
+                max_nr_requests: (self.max_nr_requests * 2).min(1023),
+                ..self
+            },
+            discovery::StorageClass::Emmc => Self {
+                max_read_ahead: self.max_read_ahead.min(1024),
+                max_nr_requests: (self.max_nr_requests / 2).max(self.min_nr_requests),
+                ..self
+            },
+            discovery::StorageClass::Rotational => Self {
+                min_read_ahead: self.min_read_ahead.max(256),
+                max_read_ahead: self.max_read_ahead.max(4096),
+                ..self
             },
         }
     }
@@ -1,2 +1,8 @@
+pub mod cleaner_quarantine;
+pub mod cleaner_rules;
+pub mod cleaner_state;
 pub mod kernel_limits;
 pub mod loop_settings;
+pub mod settings;
+pub mod thermal_profile;
+pub mod trim_state;
@@ -0,0 +1,36 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::resources::sys_paths;
+
+use std::{collections, fs, str, sync};
+
+static SETTINGS: sync::OnceLock<collections::HashMap<String, String>> = sync::OnceLock::new();
+
+fn load() -> collections::HashMap<String, String> {
+    let content = fs::read_to_string(sys_paths::K_MODULE_CONFIG_PATH).unwrap_or_default();
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with(';'))
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            Some((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+pub fn get(key: &str) -> Option<&'static str> {
+    SETTINGS.get_or_init(load).get(key).map(String::as_str)
+}
+
+pub fn get_u32(key: &str, default: u32) -> u32 {
+    get(key).and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+pub fn get_bool(key: &str, default: bool) -> bool {
+    match get(key) {
+        Some("true" | "1" | "True") => true,
+        Some("false" | "0" | "False") => false,
+        _ => default,
+    }
+}
@@ -0,0 +1,83 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::algorithms::{thermal_math, thermal_tuning};
+use crate::daemon::types;
+use crate::hal::{filesystem, properties};
+use crate::resources::sys_paths;
+
+use std::fmt::Write;
+use std::fs;
+
+const DEVICE_PROPERTY: &str = "ro.product.device";
+
+fn device_id() -> String {
+    properties::get_system_property(DEVICE_PROPERTY)
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+pub fn load(defaults: &thermal_math::ThermalConfig) -> Option<thermal_tuning::TunedGains> {
+    let content = fs::read_to_string(sys_paths::K_THERMAL_PROFILE_PATH).ok()?;
+    let device = device_id();
+    let mut matched = false;
+    let mut gains = thermal_tuning::TunedGains {
+        kp_base: defaults.kp_base,
+        ki_base: defaults.ki_base,
+        kd_fast: defaults.kd_fast,
+        smith_tau: defaults.smith_tau,
+        smith_delay_sec: defaults.smith_delay_sec,
+    };
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        if key.trim() == "device" {
+            matched = value == device;
+            continue;
+        }
+        let Ok(parsed) = value.parse::<f32>() else {
+            continue;
+        };
+        match key.trim() {
+            "kp_base" => gains.kp_base = parsed,
+            "ki_base" => gains.ki_base = parsed,
+            "kd_fast" => gains.kd_fast = parsed,
+            "smith_tau" => gains.smith_tau = parsed,
+            "smith_delay_sec" => gains.smith_delay_sec = parsed,
+            _ => {}
+        }
+    }
+    if !matched {
+        log::info!("ThermalProfile: Stored profile belongs to another device, ignoring.");
+        return None;
+    }
+    if !gains.is_plausible(defaults) {
+        log::warn!("ThermalProfile: Stored gains out of bounds, ignoring.");
+        return None;
+    }
+    Some(gains)
+}
+
+fn write_gains(body: &mut String, gains: &thermal_tuning::TunedGains) {
+    let _ = writeln!(body, "kp_base={:.4}", gains.kp_base);
+    let _ = writeln!(body, "ki_base={:.5}", gains.ki_base);
+    let _ = writeln!(body, "kd_fast={:.4}", gains.kd_fast);
+    let _ = writeln!(body, "smith_tau={:.2}", gains.smith_tau);
+    let _ = writeln!(body, "smith_delay_sec={:.2}", gains.smith_delay_sec);
+}
+
+pub fn save(gains: &thermal_tuning::TunedGains) -> Result<(), types::QosError> {
+    let mut body = String::with_capacity(192);
+    let _ = writeln!(body, "device={}", device_id());
+    write_gains(&mut body, gains);
+    filesystem::write_state_file(sys_paths::K_THERMAL_PROFILE_PATH, &body)
+}
+
+pub fn describe(gains: &thermal_tuning::TunedGains, source: &str) -> String {
+    let mut body = String::with_capacity(160);
+    let _ = writeln!(body, "source={source}");
+    write_gains(&mut body, gains);
+    body
+}
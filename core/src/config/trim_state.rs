@@ -0,0 +1,63 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::daemon::types;
+use crate::hal::filesystem;
+use crate::resources::sys_paths;
+
+use std::fmt::Write;
+use std::fs;
+
+#[derive(Debug, Clone, Default)]
+pub struct TrimRecord {
+    pub mount: String,
+    pub last_trim: u64,
+    pub last_bytes: u64,
+    pub total_bytes: u64,
+    pub pending_bytes: u64,
+    pub cursor: u64,
+}
+
+pub fn load() -> Vec<TrimRecord> {
+    let Ok(content) = fs::read_to_string(sys_paths::K_TRIM_STATE_PATH) else {
+        return Vec::new();
+    };
+    let mut records: Vec<TrimRecord> = Vec::new();
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        if key.trim() == "mount" {
+            records.push(TrimRecord {
+                mount: value.to_string(),
+                ..TrimRecord::default()
+            });
+            continue;
+        }
+        let (Some(record), Ok(parsed)) = (records.last_mut(), value.parse::<u64>()) else {
+            continue;
+        };
+        match key.trim() {
+            "last_trim" => record.last_trim = parsed,
+            "last_bytes" => record.last_bytes = parsed,
+            "total_bytes" => record.total_bytes = parsed,
+            "pending_bytes" => record.pending_bytes = parsed,
+            "cursor" => record.cursor = parsed,
+            _ => {}
+        }
+    }
+    records
+}
+
+pub fn save(records: &[TrimRecord]) -> Result<(), types::QosError> {
+    let mut body = String::with_capacity(128 * records.len());
+    for record in records {
+        let _ = writeln!(body, "mount={}", record.mount);
+        let _ = writeln!(body, "last_trim={}", record.last_trim);
+        let _ = writeln!(body, "last_bytes={}", record.last_bytes);
+        let _ = writeln!(body, "total_bytes={}", record.total_bytes);
+        let _ = writeln!(body, "pending_bytes={}", record.pending_bytes);
+        let _ = writeln!(body, "cursor={}", record.cursor);
+    }
+    filesystem::write_state_file(sys_paths::K_TRIM_STATE_PATH, &body)
+}
@@ -0,0 +1,231 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::algorithms::thermal_math;
+use crate::config::settings;
+use crate::daemon::{state, status, traits, types};
+use crate::hal::{battery, filesystem, thermal};
+use crate::resources::{discovery, sys_paths};
+
+use std::fmt::Write;
+use std::{fs, os, path, time};
+
+#[derive(Debug, Clone, Copy)]
+struct ChargingConfig {
+    interval_ms: i32,
+    target_pct: f32,
+    hysteresis_pct: f32,
+    thermal_margin: f32,
+    min_current_ratio: f32,
+    current_step_ratio: f32,
+}
+
+impl Default for ChargingConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: 10_000,
+            target_pct: settings::get_u32("charge_limit", 80).clamp(50, 100) as f32,
+            hysteresis_pct: settings::get_u32("charge_limit_hysteresis", 5).clamp(1, 20) as f32,
+            thermal_margin: 3.0,
+            min_current_ratio: 0.3,
+            current_step_ratio: 0.05,
+        }
+    }
+}
+
+struct CurrentLimit {
+    path: &'static str,
+    original: u64,
+    applied: u64,
+}
+
+pub struct ChargingController {
+    dummy_fd: rustix::fd::OwnedFd,
+    switch: Option<(&'static str, discovery::ChargeSwitchKind)>,
+    current_limit: Option<CurrentLimit>,
+    capacity_sensor: battery::BatterySensor,
+    temp_sensor: thermal::GuardedThermalSensor,
+    hard_limit_bat: f32,
+    charging_allowed: Option<bool>,
+    tunables: ChargingConfig,
+}
+
+impl ChargingController {
+    pub fn new() -> Result<Self, types::QosError> {
+        log::info!("ChargingController: Initializing...");
+        let control = sys_paths::get_charge_control();
+        let switch = control
+            .switch
+            .as_ref()
+            .and_then(|(p, kind)| Some((p.to_str()?, *kind)));
+        let current_limit = control
+            .current_max
+            .as_deref()
+            .and_then(path::Path::to_str)
+            .and_then(|p| {
+                let original = fs::read_to_string(p).ok()?.trim().parse::<u64>().ok()?;
+                (original > 0).then_some(CurrentLimit {
+                    path: p,
+                    original,
+                    applied: original,
+                })
+            });
+        if switch.is_none() && current_limit.is_none() {
+            return Err(types::QosError::SystemCheckFailed(
+                "No charge control nodes found.".to_string(),
+            ));
+        }
+        if let Some((p, _)) = switch {
+            log::info!("ChargingController: Charge switch at {p}");
+        }
+        let dummy_fd = rustix::event::eventfd(
+            0,
+            rustix::event::EventfdFlags::CLOEXEC | rustix::event::EventfdFlags::NONBLOCK,
+        )
+        .map_err(|e| types::QosError::SystemCheckFailed(format!("Eventfd fail: {e}")))?;
+        Ok(Self {
+            dummy_fd,
+            switch,
+            current_limit,
+            capacity_sensor: battery::BatterySensor::new(sys_paths::K_BATTERY_CAPACITY_PATH),
+            temp_sensor: thermal::GuardedThermalSensor::new(
+                sys_paths::get_battery_temp_candidates(),
+                35.0,
+            ),
+            hard_limit_bat: thermal_math::ThermalConfig::default().hard_limit_bat,
+            charging_allowed: None,
+            tunables: ChargingConfig::default(),
+        })
+    }
+    fn update_switch(&mut self, capacity: f32) {
+        let Some((path, kind)) = self.switch else {
+            return;
+        };
+        let desired = match self.charging_allowed {
+            Some(true) | None => capacity < self.tunables.target_pct,
+            Some(false) => capacity <= self.tunables.target_pct - self.tunables.hysteresis_pct,
+        };
+        if self.charging_allowed == Some(desired) {
+            return;
+        }
+        match filesystem::write_to_file(path, kind.value(desired)) {
+            Ok(()) => {
+                log::info!(
+                    "ChargingController: Charging {} at {capacity:.0}%",
+                    if desired { "resumed" } else { "paused" }
+                );
+                self.charging_allowed = Some(desired);
+            }
+            Err(e) => log::debug!("ChargingController: Failed to toggle {path}: {e}"),
+        }
+    }
+    fn update_current(&mut self, temp: Option<f32>) {
+        let Some(limit) = self.current_limit.as_mut() else {
+            return;
+        };
+        let margin = self.tunables.thermal_margin.max(0.1);
+        // Without a trustworthy reading, charge at the reduced current.
+        let ratio = temp.map_or(self.tunables.min_current_ratio, |temp| {
+            ((self.hard_limit_bat - temp) / margin).clamp(self.tunables.min_current_ratio, 1.0)
+        });
+        let target = (limit.original as f32 * ratio) as u64;
+        let step = (limit.original as f32 * self.tunables.current_step_ratio) as u64;
+        let restoring = target == limit.original && limit.applied != limit.original;
+        if !restoring && target.abs_diff(limit.applied) <= step {
+            return;
+        }
+        let mut buffer = itoa::Buffer::new();
+        match filesystem::write_to_file(limit.path, buffer.format(target)) {
+            Ok(()) => {
+                log::debug!(
+                    "ChargingController: Charge current {} -> {target} uA at {}",
+                    limit.applied,
+                    temp.map_or("unknown".to_string(), |t| format!("{t:.1}C"))
+                );
+                limit.applied = target;
+            }
+            Err(e) => log::debug!("ChargingController: Failed to write {}: {e}", limit.path),
+        }
+    }
+    fn publish(&self, capacity: f32, temp: Option<f32>) {
+        let mut body = String::with_capacity(128);
+        let _ = writeln!(body, "target={:.0}", self.tunables.target_pct);
+        let _ = writeln!(
+            body,
+            "resume_below={:.0}",
+            self.tunables.target_pct - self.tunables.hysteresis_pct
+        );
+        let _ = writeln!(body, "capacity={capacity:.0}");
+        match temp {
+            Some(temp) => {
+                let _ = writeln!(body, "battery_temp={temp:.1}");
+            }
+            None => {
+                let _ = writeln!(body, "battery_temp=none");
+            }
+        }
+        let _ = writeln!(
+            body,
+            "temp_sensor={} ({})",
+            self.temp_sensor.active_path().display(),
+            self.temp_sensor.health().as_str()
+        );
+        let allowed = match self.charging_allowed {
+            Some(true) => "yes",
+            Some(false) => "no",
+            None => "n/a",
+        };
+        let _ = writeln!(body, "charging_allowed={allowed}");
+        if let Some(limit) = &self.current_limit {
+            let _ = writeln!(body, "current_max={}/{}", limit.applied, limit.original);
+        }
+        status::publish("charging", body);
+    }
+}
+
+impl Drop for ChargingController {
+    fn drop(&mut self) {
+        log::info!("ChargingController: Restoring charge control.");
+        if let Some((path, kind)) = self.switch
+            && self.charging_allowed != Some(true)
+        {
+            let _ = filesystem::write_to_file(path, kind.value(true));
+        }
+        if let Some(limit) = &self.current_limit
+            && limit.applied != limit.original
+        {
+            let mut buffer = itoa::Buffer::new();
+            let _ = filesystem::write_to_file(limit.path, buffer.format(limit.original));
+        }
+    }
+}
+
+impl traits::EventHandler for ChargingController {
+    fn as_raw_fd(&self) -> os::fd::RawFd {
+        os::fd::AsRawFd::as_raw_fd(&self.dummy_fd)
+    }
+    fn on_event(
+        &mut self,
+        _context: &mut state::DaemonContext,
+    ) -> Result<traits::LoopAction, types::QosError> {
+        let mut buf = [0u8; 8];
+        let _ = rustix::io::read(&self.dummy_fd, &mut buf);
+        Ok(traits::LoopAction::Continue)
+    }
+    fn on_timeout(
+        &mut self,
+        _context: &mut state::DaemonContext,
+    ) -> Result<traits::LoopAction, types::QosError> {
+        let capacity = self.capacity_sensor.read();
+        let temp = self.temp_sensor.read_checked(time::Instant::now());
+        self.update_switch(capacity);
+        self.update_current(temp);
+        self.publish(capacity, temp);
+        Ok(traits::LoopAction::Continue)
+    }
+    fn get_timeout_ms(&self) -> i32 {
+        self.tunables.interval_ms
+    }
+    fn get_poll_flags(&self) -> rustix::event::epoll::EventFlags {
+        rustix::event::epoll::EventFlags::empty()
+    }
+}
@@ -1,230 +1,866 @@
 //! Author: [Seclususs](https://github.com/seclususs)
 
+use crate::algorithms::cleaner_math;
 use crate::bindings::sys;
-use crate::daemon::{state, traits, types};
-use crate::hal::{thermal, traversal};
-use crate::monitors::psi_monitor;
+use crate::config::{cleaner_quarantine, cleaner_rules, cleaner_state, settings};
+use crate::daemon::{state, status, traits, types};
+use crate::hal::{filesystem, thermal, traversal};
+use crate::monitors::{app_monitor, orphan_monitor, psi_monitor};
 use crate::resources::sys_paths;
 
-use std::{ffi, fs, io, os, path, sync, thread, time};
+use std::fmt::Write;
+use std::{collections, fs, io, os, path, sync, thread, time};
 
 #[derive(Debug, Clone, Copy)]
 struct CleanerConfig {
     sweep_interval_ms: i32,
-    bloat_limit_bytes: u64,
+    foreground_sample_ms: i32,
+    active_interval_ms: i32,
+    recent_foreground_window: time::Duration,
     storage_critical_threshold: f32,
-    age_stale_media: time::Duration,
-    age_stale_code: time::Duration,
-    age_bloat: time::Duration,
-    age_emergency: time::Duration,
-    age_trash: time::Duration,
+    temp_ceiling: f32,
+    emergency_temp_ceiling: f32,
+    io_psi_ceiling: f32,
+    cpu_psi_ceiling: f32,
+    emergency_cpu_psi_ceiling: f32,
+    pause_budget: time::Duration,
+    resume_interval: time::Duration,
+    orphan_grace: time::Duration,
 }
 
 impl Default for CleanerConfig {
     fn default() -> Self {
         Self {
             sweep_interval_ms: 600_000,
-            bloat_limit_bytes: 512 * 1024 * 1024,
+            foreground_sample_ms: 30_000,
+            active_interval_ms: 1000,
+            recent_foreground_window: time::Duration::from_mins(15),
             storage_critical_threshold: 10.0,
-            age_stale_media: time::Duration::from_secs(3 * 86400),
-            age_stale_code: time::Duration::from_secs(30 * 86400),
-            age_bloat: time::Duration::from_secs(24 * 3600),
-            age_emergency: time::Duration::from_secs(3600),
-            age_trash: time::Duration::from_secs(3600),
+            temp_ceiling: 40.0,
+            emergency_temp_ceiling: 46.0,
+            io_psi_ceiling: 3.0,
+            cpu_psi_ceiling: 3.0,
+            emergency_cpu_psi_ceiling: 80.0,
+            pause_budget: time::Duration::from_secs(30),
+            resume_interval: time::Duration::from_mins(2),
+            orphan_grace: time::Duration::from_hours(72),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum CleanReason {
+    Trash,
+    Age,
+    Bloat,
+    Emergency,
+    Quota,
+}
+
+type ReasonTally = [ReportEntry; CleanReason::ALL.len()];
+
+impl CleanReason {
+    const ALL: [CleanReason; 5] = [
+        CleanReason::Trash,
+        CleanReason::Age,
+        CleanReason::Bloat,
+        CleanReason::Emergency,
+        CleanReason::Quota,
+    ];
+    fn as_str(self) -> &'static str {
+        match self {
+            CleanReason::Trash => "trash",
+            CleanReason::Age => "age",
+            CleanReason::Bloat => "bloat",
+            CleanReason::Emergency => "emergency",
+            CleanReason::Quota => "quota",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct ReportEntry {
+    files: u64,
+    bytes: u64,
+    oldest: u64,
+    newest: u64,
+}
+
+impl ReportEntry {
+    fn add(&mut self, size: u64, mtime: u64) {
+        if self.files == 0 {
+            self.oldest = mtime;
+            self.newest = mtime;
+        } else {
+            self.oldest = self.oldest.min(mtime);
+            self.newest = self.newest.max(mtime);
+        }
+        self.files += 1;
+        self.bytes = self.bytes.saturating_add(size);
+    }
+    fn merge(&mut self, other: &ReportEntry) {
+        if other.files == 0 {
+            return;
+        }
+        if self.files == 0 {
+            *self = *other;
+            return;
+        }
+        self.files += other.files;
+        self.bytes = self.bytes.saturating_add(other.bytes);
+        self.oldest = self.oldest.min(other.oldest);
+        self.newest = self.newest.max(other.newest);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OrphanMode {
+    Off,
+    Report,
+    Delete,
+}
+
+impl OrphanMode {
+    fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("off" | "false" | "0") => OrphanMode::Off,
+            Some("delete") => OrphanMode::Delete,
+            _ => OrphanMode::Report,
+        }
+    }
+    fn as_str(self) -> &'static str {
+        match self {
+            OrphanMode::Off => "off",
+            OrphanMode::Report => "report",
+            OrphanMode::Delete => "delete",
+        }
+    }
+}
+
+struct OrphanEntry {
+    orphan: orphan_monitor::Orphan,
+    bytes: u64,
+    first_seen: u64,
+    state: &'static str,
+}
+
+#[derive(Default)]
+struct CycleReport {
+    entries: collections::BTreeMap<(String, u32, String, CleanReason), ReportEntry>,
+    skipped: collections::BTreeMap<(u32, String), &'static str>,
+    orphans: Vec<OrphanEntry>,
+    total: ReportEntry,
+    cursor: Option<(String, String)>,
+}
+
+impl CycleReport {
+    fn merge(&mut self, rule: &str, target: &SweepTarget, tally: &ReasonTally) {
+        for (reason, entry) in CleanReason::ALL.iter().zip(tally) {
+            if entry.files == 0 {
+                continue;
+            }
+            self.total.merge(entry);
+            self.entries
+                .entry((
+                    rule.to_string(),
+                    target.user,
+                    target.package.as_deref().unwrap_or("-").to_string(),
+                    *reason,
+                ))
+                .or_default()
+                .merge(entry);
+        }
+    }
+    fn skip(&mut self, user: u32, package: &str, reason: &'static str) {
+        self.skipped.insert((user, package.to_string()), reason);
+    }
+    fn render(&self, dry_run: bool, emergency: bool, source: &str) -> String {
+        let mut body = String::with_capacity(128 * (self.entries.len() + 4));
+        let _ = writeln!(body, "generated={}", unix_now());
+        let _ = writeln!(body, "mode={}", if dry_run { "report" } else { "delete" });
+        let _ = writeln!(body, "emergency={emergency}");
+        let _ = writeln!(body, "rules={source}");
+        let _ = writeln!(body, "complete={}", self.cursor.is_none());
+        if let Some((rule, path)) = &self.cursor {
+            let _ = writeln!(body, "resume_from={rule} {path}");
+        }
+        let _ = writeln!(body, "files={}", self.total.files);
+        let _ = writeln!(body, "bytes={}", self.total.bytes);
+        for ((rule, user, package, reason), entry) in &self.entries {
+            let _ = writeln!(
+                body,
+                "rule={rule} user={user} package={package} reason={} files={} bytes={} oldest={} newest={}",
+                reason.as_str(),
+                entry.files,
+                entry.bytes,
+                entry.oldest,
+                entry.newest
+            );
+        }
+        for ((user, package), reason) in &self.skipped {
+            let _ = writeln!(
+                body,
+                "skipped user={user} package={package} reason={reason}"
+            );
+        }
+        for entry in &self.orphans {
+            let _ = writeln!(
+                body,
+                "orphan kind={} package={} path={} bytes={} first_seen={} state={}",
+                entry.orphan.kind.as_str(),
+                entry.orphan.package,
+                entry.orphan.path.display(),
+                entry.bytes,
+                entry.first_seen,
+                entry.state
+            );
+        }
+        body
+    }
+}
+
+fn unix_secs(t: time::SystemTime) -> u64 {
+    t.duration_since(time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn unix_now() -> u64 {
+    unix_secs(time::SystemTime::now())
+}
+
+#[derive(Default)]
+struct SweepGate {
+    running: sync::atomic::AtomicBool,
+    paused: sync::atomic::AtomicBool,
+    stop: sync::atomic::AtomicBool,
+    resume: sync::atomic::AtomicBool,
+}
+
+impl SweepGate {
+    fn is_stopped(&self) -> bool {
+        self.stop.load(sync::atomic::Ordering::Acquire)
+    }
+    fn should_stop(&self) -> bool {
+        while self.paused.load(sync::atomic::Ordering::Acquire) && !self.is_stopped() {
+            thread::sleep(time::Duration::from_millis(250));
+        }
+        self.is_stopped()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Criterion {
+    Age(Option<(time::Duration, CleanReason)>),
+    LastUse(u64),
+}
+
+fn last_use(meta: &fs::Metadata, modified: time::SystemTime) -> u64 {
+    cleaner_math::last_use(meta.accessed().ok().map(unix_secs), unix_secs(modified))
+}
+
+fn file_key(meta: &fs::Metadata) -> (u64, u64) {
+    (
+        os::unix::fs::MetadataExt::dev(meta),
+        os::unix::fs::MetadataExt::ino(meta),
+    )
+}
+
+struct SweepTarget {
+    dir: path::PathBuf,
+    user: u32,
+    package: Option<String>,
+    open: collections::HashSet<(u64, u64)>,
+}
+
+struct SweepVisitor<'a> {
+    policy: &'a cleaner_rules::RulePolicy<'a>,
+    open: &'a collections::HashSet<(u64, u64)>,
+    gate: &'a SweepGate,
+    criterion: Criterion,
+    is_critical: bool,
+    dry_run: bool,
+    now: time::SystemTime,
+    tally: ReasonTally,
+    pending: Option<(CleanReason, u64, u64)>,
+}
+
+impl SweepVisitor<'_> {
+    fn record(&mut self, (reason, size, mtime): (CleanReason, u64, u64)) {
+        self.tally[reason as usize].add(size, mtime);
+    }
+    fn verdict(
+        &self,
+        meta: &fs::Metadata,
+        modified: time::SystemTime,
+        is_trash: bool,
+    ) -> Option<CleanReason> {
+        let age = match self.criterion {
+            Criterion::LastUse(cutoff) => {
+                return (last_use(meta, modified) <= cutoff).then_some(CleanReason::Quota);
+            }
+            Criterion::Age(age) => age,
+        };
+        let trash = self
+            .policy
+            .trash_age
+            .filter(|_| is_trash)
+            .map(|t| (t, CleanReason::Trash));
+        let (threshold, reason) = match (trash, age) {
+            (Some(trash), Some(age)) if trash.0 < age.0 => trash,
+            (_, Some(age)) => age,
+            (Some(trash), None) => trash,
+            (None, None) => return None,
+        };
+        let diff = self.now.duration_since(modified).ok()?;
+        (diff > threshold).then_some(reason)
+    }
+}
+
+impl traversal::Visitor for SweepVisitor<'_> {
+    fn visit(&mut self, entry: &fs::DirEntry, _depth: usize) -> traversal::TraversalAction {
+        self.pending = None;
+        let file_name = entry.file_name();
+        let name = os::unix::ffi::OsStrExt::as_bytes(file_name.as_os_str());
+        if self.policy.is_excluded(name, self.is_critical) {
+            return traversal::TraversalAction::Keep;
+        }
+        let is_trash = self.policy.is_trash(name);
+        if !is_trash && !self.policy.is_included(name) {
+            return traversal::TraversalAction::Keep;
+        }
+        let Ok(meta) = entry.metadata() else {
+            return traversal::TraversalAction::Keep;
+        };
+        if self.open.contains(&file_key(&meta)) {
+            return traversal::TraversalAction::Keep;
+        }
+        let Ok(modified) = meta.modified() else {
+            return traversal::TraversalAction::Keep;
+        };
+        let Some(reason) = self.verdict(&meta, modified, is_trash) else {
+            return traversal::TraversalAction::Keep;
+        };
+        let candidate = (reason, meta.len(), unix_secs(modified));
+        if self.dry_run {
+            self.record(candidate);
+            return traversal::TraversalAction::Keep;
+        }
+        self.pending = Some(candidate);
+        traversal::TraversalAction::DeleteFile
+    }
+    fn removed(&mut self, _entry: &fs::DirEntry) {
+        if let Some(candidate) = self.pending.take() {
+            self.record(candidate);
+        }
+    }
+    fn should_stop(&mut self) -> bool {
+        self.gate.should_stop()
+    }
+}
+
+struct QuotaUsage {
+    target: usize,
+    used: u64,
+    samples: Vec<(u64, u64)>,
+}
+
+struct QuotaScan<'a> {
+    policy: &'a cleaner_rules::RulePolicy<'a>,
+    open: &'a collections::HashSet<(u64, u64)>,
+    gate: &'a SweepGate,
+    is_critical: bool,
+    used: u64,
+    samples: Vec<(u64, u64)>,
+}
+
+impl traversal::Visitor for QuotaScan<'_> {
+    fn visit(&mut self, entry: &fs::DirEntry, _depth: usize) -> traversal::TraversalAction {
+        let Ok(meta) = entry.metadata() else {
+            return traversal::TraversalAction::Keep;
+        };
+        self.used = self.used.saturating_add(meta.len());
+        let file_name = entry.file_name();
+        let name = os::unix::ffi::OsStrExt::as_bytes(file_name.as_os_str());
+        let evictable = !self.open.contains(&file_key(&meta))
+            && !self.policy.is_excluded(name, self.is_critical)
+            && (self.policy.is_trash(name) || self.policy.is_included(name));
+        if evictable && let Ok(modified) = meta.modified() {
+            self.samples.push((last_use(&meta, modified), meta.len()));
+        }
+        traversal::TraversalAction::Keep
+    }
+    fn should_stop(&mut self) -> bool {
+        self.gate.should_stop()
+    }
+}
+
+struct SizeScan<'a> {
+    gate: &'a SweepGate,
+    bytes: u64,
+}
+
+impl traversal::Visitor for SizeScan<'_> {
+    fn visit(&mut self, entry: &fs::DirEntry, _depth: usize) -> traversal::TraversalAction {
+        if let Ok(meta) = entry.metadata() {
+            self.bytes = self.bytes.saturating_add(meta.len());
+        }
+        traversal::TraversalAction::Keep
+    }
+    fn should_stop(&mut self) -> bool {
+        self.gate.should_stop()
+    }
+}
+
+struct CleanRequest {
+    is_critical: bool,
+    recent: collections::HashSet<String>,
+}
+
 struct CleanerWorker {
-    tunables: CleanerConfig,
-    rx: sync::mpsc::Receiver<()>,
+    rules: cleaner_rules::CleanerRules,
+    dry_run: bool,
+    totals: cleaner_state::CleanerTotals,
+    last_files: u64,
+    last_bytes: u64,
+    last_skipped: usize,
+    users: Vec<u32>,
+    orphan_mode: OrphanMode,
+    orphan_grace: time::Duration,
+    quarantine: collections::BTreeMap<String, cleaner_quarantine::QuarantineEntry>,
+    gate: sync::Arc<SweepGate>,
+    rx: sync::mpsc::Receiver<CleanRequest>,
 }
 
 impl CleanerWorker {
-    fn new(tunables: CleanerConfig, rx: sync::mpsc::Receiver<()>) -> Self {
-        Self { tunables, rx }
-    }
     fn run(&mut self) {
-        while self.rx.recv().is_ok() {
-            let items = self.perform_cycle();
-            if items > 0 {
-                log::info!("Cleaner: Cycle complete. Removed {items} items.");
+        self.publish();
+        while let Ok(request) = self.rx.recv() {
+            self.users = app_monitor::list_users()
+                .into_iter()
+                .filter(|user| settings::get_bool(&format!("cleaner_user_{user}"), true))
+                .collect();
+            let mut report = self.perform_cycle(request.is_critical, &request.recent);
+            if report.cursor.is_none() {
+                self.clean_orphans(&mut report);
             }
+            self.finish_cycle(&report, request.is_critical);
             unsafe {
                 sys::mallopt(-101, 0);
             }
+            self.gate.resume.store(
+                self.totals.cursor.is_some(),
+                sync::atomic::Ordering::Release,
+            );
+            self.gate
+                .running
+                .store(false, sync::atomic::Ordering::Release);
         }
     }
-    fn is_storage_critical(&self) -> bool {
-        if let Ok(stats) = rustix::fs::statvfs("/data") {
-            let total = stats.f_blocks * stats.f_frsize;
-            let free = stats.f_bavail * stats.f_frsize;
-            if total > 0 {
-                let pct = (free as f32 / total as f32) * 100.0;
-                return pct < self.tunables.storage_critical_threshold;
+    fn clean_orphans(&mut self, report: &mut CycleReport) {
+        if self.orphan_mode == OrphanMode::Off {
+            return;
+        }
+        let packages = app_monitor::load_packages();
+        if packages.is_empty() {
+            log::debug!("Cleaner: Package list unavailable, skipping orphan scan.");
+            return;
+        }
+        let records = app_monitor::load_package_records();
+        let now = unix_now();
+        let delete = self.orphan_mode == OrphanMode::Delete && !self.dry_run;
+        let mut quarantine = collections::BTreeMap::new();
+        for orphan in orphan_monitor::scan(&packages, &self.users) {
+            if self.gate.should_stop() {
+                return;
+            }
+            let Ok(meta) = fs::symlink_metadata(&orphan.path) else {
+                continue;
+            };
+            let key = orphan.path.display().to_string();
+            let entry = match self.quarantine.get(&key) {
+                Some(entry) => *entry,
+                None if meta.is_dir() => {
+                    let mut scan = SizeScan {
+                        gate: &self.gate,
+                        bytes: 0,
+                    };
+                    traversal::walk_and_act(&orphan.path, &mut scan, 0);
+                    if self.gate.is_stopped() {
+                        return;
+                    }
+                    cleaner_quarantine::QuarantineEntry {
+                        first_seen: now,
+                        bytes: scan.bytes,
+                    }
+                }
+                None => cleaner_quarantine::QuarantineEntry {
+                    first_seen: now,
+                    bytes: meta.len(),
+                },
+            };
+            let state = if now.saturating_sub(entry.first_seen) < self.orphan_grace.as_secs() {
+                "quarantined"
+            } else if !orphan.is_removable(records.as_ref()) {
+                "report_only"
+            } else if !delete {
+                "eligible"
+            } else {
+                let removed = if meta.is_dir() {
+                    fs::remove_dir_all(&orphan.path)
+                } else {
+                    fs::remove_file(&orphan.path)
+                };
+                match removed {
+                    Ok(()) => "removed",
+                    Err(e) => {
+                        log::warn!("Cleaner: Failed to remove orphan {key}: {e}");
+                        "failed"
+                    }
+                }
+            };
+            if state != "removed" {
+                quarantine.insert(key, entry);
+            }
+            if state == "removed" || (state == "eligible" && self.dry_run) {
+                let mtime = meta.modified().map_or(entry.first_seen, unix_secs);
+                report.total.add(entry.bytes, mtime);
             }
+            report.orphans.push(OrphanEntry {
+                orphan,
+                bytes: entry.bytes,
+                first_seen: entry.first_seen,
+                state,
+            });
+        }
+        self.quarantine = quarantine;
+        if let Err(e) = cleaner_quarantine::save(&self.quarantine) {
+            log::warn!("Cleaner: Failed to save orphan quarantine: {e}");
         }
-        false
     }
-    #[inline]
-    fn is_safe_name(name: &ffi::OsStr) -> bool {
-        let bytes = os::unix::ffi::OsStrExt::as_bytes(name);
-        if bytes.ends_with(b".db")
-            || bytes.ends_with(b".xml")
-            || bytes.ends_with(b".obb")
-            || bytes.ends_with(b".pak")
-            || bytes.ends_with(b".dat")
-            || bytes.ends_with(b".json")
-            || bytes.ends_with(b".lock")
-            || bytes.ends_with(b".pref")
-            || bytes.ends_with(b".conf")
-        {
-            return true;
+    fn finish_cycle(&mut self, report: &CycleReport, is_critical: bool) {
+        let files = report.total.files;
+        let bytes = report.total.bytes;
+        self.last_files = files;
+        self.last_bytes = bytes;
+        self.last_skipped = report.skipped.len();
+        self.totals.cycles += 1;
+        self.totals.last_cycle = unix_now();
+        self.totals.cursor.clone_from(&report.cursor);
+        if let Some((rule, path)) = &report.cursor {
+            log::info!("Cleaner: Sweep interrupted at {rule} {path}, will resume there.");
         }
-        if bytes.ends_with(b"-journal") || bytes.ends_with(b"-wal") || bytes.ends_with(b"-shm") {
-            return true;
+        if self.dry_run {
+            log::info!(
+                "Cleaner: Report complete. {files} items ({} MiB) eligible.",
+                bytes >> 20
+            );
+        } else {
+            self.totals.files = self.totals.files.saturating_add(files);
+            self.totals.bytes = self.totals.bytes.saturating_add(bytes);
+            if files > 0 {
+                log::info!(
+                    "Cleaner: Cycle complete. Removed {files} items ({} MiB).",
+                    bytes >> 20
+                );
+            }
         }
-        false
+        if let Err(e) = cleaner_state::save(&self.totals) {
+            log::warn!("Cleaner: Failed to save totals: {e}");
+        }
+        let body = report.render(self.dry_run, is_critical, self.rules.source);
+        if let Err(e) = filesystem::write_state_file(sys_paths::K_CLEANER_REPORT_PATH, &body) {
+            log::warn!("Cleaner: Failed to write report: {e}");
+        }
+        self.publish();
     }
-    #[inline]
-    fn is_trash_ext(name: &ffi::OsStr) -> bool {
-        let bytes = os::unix::ffi::OsStrExt::as_bytes(name);
-        bytes.ends_with(b".tmp")
-            || bytes.ends_with(b".temp")
-            || bytes.ends_with(b".log")
-            || bytes.ends_with(b".bak")
-            || bytes.ends_with(b".old")
-            || bytes.ends_with(b".thumb")
-            || bytes.ends_with(b".exo")
-    }
-    fn perform_cycle(&mut self) -> usize {
-        let is_critical = self.is_storage_critical();
-        let now = time::SystemTime::now();
-        let mut total_cleaned = 0;
-        total_cleaned += self.clean_system_paths(now);
-        total_cleaned += self.clean_app_caches(is_critical, now);
-        total_cleaned
-    }
-    fn clean_system_paths(&self, now: time::SystemTime) -> usize {
-        let mut cleaned = 0;
-        let tunables = self.tunables;
-        for sys in ["/data/anr", "/data/tombstones"] {
-            let p = path::Path::new(sys);
-            if p.exists() {
-                let policy = |entry: &fs::DirEntry, _depth: usize| -> traversal::TraversalAction {
-                    if Self::is_safe_name(&entry.file_name()) {
-                        return traversal::TraversalAction::Keep;
-                    }
-                    if let Ok(meta) = entry.metadata() {
-                        let threshold = if Self::is_trash_ext(&entry.file_name()) {
-                            tunables.age_trash
-                        } else {
-                            tunables.age_stale_media
-                        };
-                        if let Ok(modified) = meta.modified()
-                            && let Ok(diff) = now.duration_since(modified)
-                            && diff > threshold
-                        {
-                            return traversal::TraversalAction::DeleteFile;
-                        }
-                    }
-                    traversal::TraversalAction::Keep
-                };
-                cleaned += traversal::walk_and_act(p, &policy, 0);
+    fn publish(&self) {
+        let totals = &self.totals;
+        let mode = if self.dry_run { "eligible" } else { "freed" };
+        let last = if totals.last_cycle == 0 {
+            "never".to_string()
+        } else {
+            format!("{}m", unix_now().saturating_sub(totals.last_cycle) / 60)
+        };
+        let mut body = String::with_capacity(256);
+        let users: Vec<String> = self.users.iter().map(u32::to_string).collect();
+        let _ = writeln!(
+            body,
+            "mode={} rules={} ({}) users={}",
+            if self.dry_run { "report" } else { "delete" },
+            self.rules.rules.len(),
+            self.rules.source,
+            if users.is_empty() {
+                "-".to_string()
+            } else {
+                users.join(",")
             }
+        );
+        let _ = writeln!(
+            body,
+            "last_cycle={last} {mode}_files={} {mode}_kib={} skipped_active={} complete={}",
+            self.last_files,
+            self.last_bytes >> 10,
+            self.last_skipped,
+            totals.cursor.is_none()
+        );
+        if let Some((rule, path)) = &totals.cursor {
+            let _ = writeln!(body, "resume_from={rule} {path}");
         }
-        cleaned
+        let _ = writeln!(
+            body,
+            "cycles={} total_files={} total_mib={}",
+            totals.cycles,
+            totals.files,
+            totals.bytes >> 20
+        );
+        let _ = writeln!(
+            body,
+            "orphans={} quarantined={}",
+            self.orphan_mode.as_str(),
+            self.quarantine.len()
+        );
+        let _ = writeln!(body, "report={}", sys_paths::K_CLEANER_REPORT_PATH);
+        status::publish("cleaner", body);
     }
-    fn clean_app_caches(&self, is_critical: bool, now: time::SystemTime) -> usize {
-        let mut cleaned = 0;
-        let tunables = self.tunables;
-        for root in ["/data/data", "/sdcard/Android/data"] {
-            let root_path = path::Path::new(root);
-            if !root_path.exists() {
+    fn perform_cycle(
+        &self,
+        is_critical: bool,
+        recent: &collections::HashSet<String>,
+    ) -> CycleReport {
+        let now = time::SystemTime::now();
+        let mut report = CycleReport::default();
+        let running = app_monitor::RunningApps::scan(&app_monitor::load_packages());
+        log::debug!(
+            "Cleaner: {} packages running, {} recently in foreground, users {:?}.",
+            running.count(),
+            recent.len(),
+            self.users
+        );
+        let mut resume = self.totals.cursor.as_ref().filter(|(name, _)| {
+            self.rules
+                .rules
+                .iter()
+                .any(|rule| rule.name == *name && (is_critical || !rule.emergency_only))
+        });
+        for rule in &self.rules.rules {
+            if rule.emergency_only && !is_critical {
                 continue;
             }
-            if let Ok(entries) = fs::read_dir(root_path) {
-                for entry in entries.flatten() {
-                    if let Ok(ft) = entry.file_type() {
-                        if !ft.is_dir() {
+            let from = match resume {
+                Some((name, _)) if *name != rule.name => continue,
+                Some((_, path)) => path::Path::new(path.as_str()),
+                None => path::Path::new(""),
+            };
+            resume = None;
+            let targets = self.collect_targets(rule, &running, recent, &mut report);
+            for target in targets.iter().filter(|target| target.dir.as_path() >= from) {
+                if self.gate.should_stop() {
+                    report.cursor = Some((rule.name.clone(), target.dir.display().to_string()));
+                    return report;
+                }
+                let Some(policy) = self.rules.resolve(rule, target.package.as_deref()) else {
+                    continue;
+                };
+                let age = Self::select_age(&target.dir, &policy, is_critical);
+                if age.is_none() && (rule.trash.is_empty() || policy.trash_age.is_none()) {
+                    continue;
+                }
+                let tally = self.sweep(target, &policy, Criterion::Age(age), is_critical, now);
+                report.merge(&rule.name, target, &tally);
+                if self.gate.is_stopped() {
+                    report.cursor = Some((rule.name.clone(), target.dir.display().to_string()));
+                    return report;
+                }
+            }
+            if rule.mode == cleaner_rules::CleanMode::Quota {
+                self.enforce_quota(rule, &targets, is_critical, now, &mut report);
+                if self.gate.is_stopped() {
+                    report.cursor = Some((rule.name.clone(), String::new()));
+                    return report;
+                }
+            }
+        }
+        report
+    }
+    fn collect_targets(
+        &self,
+        rule: &cleaner_rules::CleanerRule,
+        running: &app_monitor::RunningApps,
+        recent: &collections::HashSet<String>,
+        report: &mut CycleReport,
+    ) -> Vec<SweepTarget> {
+        let mut targets = Vec::new();
+        let patterns = self.users.iter().flat_map(|&user| {
+            rule.paths
+                .iter()
+                .filter_map(move |pattern| cleaner_rules::user_path(pattern, user))
+                .map(move |pattern| (user, pattern))
+        });
+        for (user, pattern) in patterns {
+            for (dir, package) in traversal::expand_glob(&pattern) {
+                let mut open = collections::HashSet::new();
+                if let Some(name) = package.as_deref()
+                    && let Some(policy) = self.rules.resolve(rule, Some(name))
+                {
+                    match policy.active_apps {
+                        cleaner_rules::ActiveApps::Ignore => {}
+                        _ if recent.contains(name) => {
+                            report.skip(user, name, "foreground");
                             continue;
                         }
-                    } else {
-                        continue;
-                    }
-                    let app_dir = entry.path();
-                    let cache_dir = app_dir.join("cache");
-                    if cache_dir.exists() {
-                        let size = if is_critical {
-                            0
-                        } else {
-                            traversal::get_tree_size_capped(
-                                &cache_dir,
-                                tunables.bloat_limit_bytes + 1024,
-                            )
-                        };
-                        let age = if is_critical {
-                            tunables.age_emergency
-                        } else if size > tunables.bloat_limit_bytes {
-                            tunables.age_bloat
-                        } else {
-                            tunables.age_stale_media
-                        };
-                        let policy =
-                            |entry: &fs::DirEntry, _depth: usize| -> traversal::TraversalAction {
-                                if !is_critical && Self::is_safe_name(&entry.file_name()) {
-                                    return traversal::TraversalAction::Keep;
-                                }
-                                if let Ok(meta) = entry.metadata() {
-                                    let threshold = if Self::is_trash_ext(&entry.file_name()) {
-                                        tunables.age_trash
-                                    } else {
-                                        age
-                                    };
-                                    if let Ok(modified) = meta.modified()
-                                        && let Ok(diff) = now.duration_since(modified)
-                                        && diff > threshold
-                                    {
-                                        return traversal::TraversalAction::DeleteFile;
-                                    }
-                                }
-                                traversal::TraversalAction::Keep
-                            };
-                        cleaned += traversal::walk_and_act(&cache_dir, &policy, 0);
-                    }
-                    let code_dir = app_dir.join("code_cache");
-                    if code_dir.exists() {
-                        let age = if is_critical {
-                            tunables.age_emergency
-                        } else {
-                            tunables.age_stale_code
-                        };
-                        let policy =
-                            |entry: &fs::DirEntry, _depth: usize| -> traversal::TraversalAction {
-                                if !is_critical && Self::is_safe_name(&entry.file_name()) {
-                                    return traversal::TraversalAction::Keep;
-                                }
-                                if let Ok(meta) = entry.metadata() {
-                                    let threshold = if Self::is_trash_ext(&entry.file_name()) {
-                                        tunables.age_trash
-                                    } else {
-                                        age
-                                    };
-                                    if let Ok(modified) = meta.modified()
-                                        && let Ok(diff) = now.duration_since(modified)
-                                        && diff > threshold
-                                    {
-                                        return traversal::TraversalAction::DeleteFile;
-                                    }
-                                }
-                                traversal::TraversalAction::Keep
-                            };
-                        cleaned += traversal::walk_and_act(&code_dir, &policy, 0);
+                        cleaner_rules::ActiveApps::Skip if running.is_running(user, name) => {
+                            report.skip(user, name, "running");
+                            continue;
+                        }
+                        cleaner_rules::ActiveApps::OpenFiles if running.is_running(user, name) => {
+                            open = running.open_files(user, name);
+                        }
+                        _ => {}
                     }
                 }
+                targets.push(SweepTarget {
+                    dir,
+                    user,
+                    package,
+                    open,
+                });
             }
         }
-        cleaned
+        targets.sort_by(|a, b| a.dir.cmp(&b.dir));
+        targets.dedup_by(|a, b| a.dir == b.dir);
+        targets
+    }
+    fn sweep(
+        &self,
+        target: &SweepTarget,
+        policy: &cleaner_rules::RulePolicy,
+        criterion: Criterion,
+        is_critical: bool,
+        now: time::SystemTime,
+    ) -> ReasonTally {
+        let mut visitor = SweepVisitor {
+            policy,
+            open: &target.open,
+            gate: &self.gate,
+            criterion,
+            is_critical,
+            dry_run: self.dry_run,
+            now,
+            tally: ReasonTally::default(),
+            pending: None,
+        };
+        traversal::walk_and_act(&target.dir, &mut visitor, 0);
+        visitor.tally
+    }
+    fn enforce_quota(
+        &self,
+        rule: &cleaner_rules::CleanerRule,
+        targets: &[SweepTarget],
+        is_critical: bool,
+        now: time::SystemTime,
+        report: &mut CycleReport,
+    ) {
+        let mut scanned: Vec<QuotaUsage> = Vec::with_capacity(targets.len());
+        for (index, target) in targets.iter().enumerate() {
+            if self.gate.should_stop() {
+                return;
+            }
+            let Some(policy) = self.rules.resolve(rule, target.package.as_deref()) else {
+                continue;
+            };
+            let mut scan = QuotaScan {
+                policy: &policy,
+                open: &target.open,
+                gate: &self.gate,
+                is_critical,
+                used: 0,
+                samples: Vec::new(),
+            };
+            traversal::walk_and_act(&target.dir, &mut scan, 0);
+            if self.gate.is_stopped() {
+                return;
+            }
+            let (mut used, mut samples) = (scan.used, scan.samples);
+            if let Some(quota) = policy.app_quota
+                && let Some(cutoff) = cleaner_math::eviction_cutoff(&mut samples, used, quota)
+            {
+                let tally = self.sweep(
+                    target,
+                    &policy,
+                    Criterion::LastUse(cutoff),
+                    is_critical,
+                    now,
+                );
+                report.merge(&rule.name, target, &tally);
+                used = used.saturating_sub(tally[CleanReason::Quota as usize].bytes);
+                samples.retain(|&(last_use, _)| last_use > cutoff);
+            }
+            scanned.push(QuotaUsage {
+                target: index,
+                used,
+                samples,
+            });
+        }
+        let Some(total_quota) = rule.total_quota else {
+            return;
+        };
+        if self.gate.is_stopped() {
+            return;
+        }
+        let used: u64 = scanned.iter().map(|usage| usage.used).sum();
+        let mut all: Vec<(u64, u64)> = scanned
+            .iter()
+            .flat_map(|usage| usage.samples.iter().copied())
+            .collect();
+        let Some(cutoff) = cleaner_math::eviction_cutoff(&mut all, used, total_quota) else {
+            return;
+        };
+        log::info!(
+            "Cleaner: Rule {} uses {} MiB of its {} MiB quota.",
+            rule.name,
+            used >> 20,
+            total_quota >> 20
+        );
+        for usage in &scanned {
+            if self.gate.should_stop() {
+                return;
+            }
+            if !usage
+                .samples
+                .iter()
+                .any(|&(last_use, _)| last_use <= cutoff)
+            {
+                continue;
+            }
+            let target = &targets[usage.target];
+            let Some(policy) = self.rules.resolve(rule, target.package.as_deref()) else {
+                continue;
+            };
+            let tally = self.sweep(
+                target,
+                &policy,
+                Criterion::LastUse(cutoff),
+                is_critical,
+                now,
+            );
+            report.merge(&rule.name, target, &tally);
+        }
+    }
+    fn select_age(
+        dir: &path::Path,
+        policy: &cleaner_rules::RulePolicy,
+        is_critical: bool,
+    ) -> Option<(time::Duration, CleanReason)> {
+        if is_critical && let Some(age) = policy.emergency_age {
+            return Some((age, CleanReason::Emergency));
+        }
+        if !is_critical
+            && let (Some(limit), Some(age)) = (policy.bloat_size, policy.bloat_age)
+            && traversal::get_tree_size_capped(dir, limit + 1024) > limit
+        {
+            return Some((age, CleanReason::Bloat));
+        }
+        policy.max_age.map(|age| (age, CleanReason::Age))
     }
 }
 
@@ -234,8 +870,13 @@ pub struct CleanerController {
     thermal: thermal::ThermalSensor,
     tunables: CleanerConfig,
     last_sweep: time::Instant,
+    last_sample: time::Instant,
     dummy_fd: fs::File,
-    tx: sync::mpsc::Sender<()>,
+    foreground: app_monitor::ForegroundTracker,
+    gate: sync::Arc<SweepGate>,
+    is_emergency: bool,
+    blocked_since: Option<time::Instant>,
+    tx: sync::mpsc::Sender<CleanRequest>,
 }
 
 impl CleanerController {
@@ -251,29 +892,68 @@ impl CleanerController {
         let dummy = unsafe { os::fd::FromRawFd::from_raw_fd(os::fd::IntoRawFd::into_raw_fd(evt)) };
         let tunables = CleanerConfig::default();
         let (tx, rx) = sync::mpsc::channel();
-        let worker_tunables = tunables;
+        let rules = cleaner_rules::load();
+        let dry_run = settings::get_bool("cleaner_dry_run", false);
+        let orphan_mode = OrphanMode::from_setting(settings::get("cleaner_orphans"));
+        log::info!(
+            "CleanerController: Using {} rules ({}){}.",
+            rules.rules.len(),
+            rules.source,
+            if dry_run { ", report only" } else { "" }
+        );
+        let totals = cleaner_state::load();
+        let gate = sync::Arc::new(SweepGate::default());
+        gate.resume
+            .store(totals.cursor.is_some(), sync::atomic::Ordering::Release);
+        let worker_gate = sync::Arc::clone(&gate);
         thread::Builder::new()
             .name("CleanerWorker".into())
             .stack_size(64 * 1024)
             .spawn(move || {
-                let mut worker = CleanerWorker::new(worker_tunables, rx);
+                let mut worker = CleanerWorker {
+                    rules,
+                    dry_run,
+                    totals,
+                    last_files: 0,
+                    last_bytes: 0,
+                    last_skipped: 0,
+                    users: Vec::new(),
+                    orphan_mode,
+                    orphan_grace: tunables.orphan_grace,
+                    quarantine: cleaner_quarantine::load(),
+                    gate: worker_gate,
+                    rx,
+                };
                 worker.run();
             })
             .map_err(|e| {
                 types::QosError::SystemCheckFailed(format!("Failed to spawn cleaner thread: {e}"))
             })?;
+        let now = time::Instant::now();
         Ok(Self {
             io_monitor: psi_monitor::PsiMonitor::new(sys_paths::K_PSI_IO_PATH)?,
             cpu_monitor: psi_monitor::PsiMonitor::new(sys_paths::K_PSI_CPU_PATH)?,
             thermal: thermal::ThermalSensor::new(sys_paths::K_BATTERY_TEMP_PATH, 35.0),
             tunables,
-            last_sweep: time::Instant::now()
+            last_sweep: now
                 .checked_sub(time::Duration::from_secs(86000))
-                .unwrap_or_else(time::Instant::now),
+                .unwrap_or(now),
+            last_sample: now
+                .checked_sub(time::Duration::from_millis(
+                    tunables.foreground_sample_ms as u64,
+                ))
+                .unwrap_or(now),
             dummy_fd: dummy,
+            foreground: app_monitor::ForegroundTracker::new(),
+            gate,
+            is_emergency: false,
+            blocked_since: None,
             tx,
         })
     }
+    fn is_running(&self) -> bool {
+        self.gate.running.load(sync::atomic::Ordering::Acquire)
+    }
     fn is_storage_critical(&self) -> bool {
         if let Ok(stats) = rustix::fs::statvfs("/data") {
             let total = stats.f_blocks * stats.f_frsize;
@@ -285,6 +965,80 @@ impl CleanerController {
         }
         false
     }
+    fn current_blocker(&mut self, is_emergency: bool) -> Option<&'static str> {
+        let tunables = &self.tunables;
+        let temp_ceiling = if is_emergency {
+            tunables.emergency_temp_ceiling
+        } else {
+            tunables.temp_ceiling
+        };
+        if self.thermal.read() > temp_ceiling {
+            return Some("thermal");
+        }
+        let io_avg10 = self.io_monitor.read_state().map_or(0.0, |d| d.some.avg10);
+        if !is_emergency && io_avg10 > tunables.io_psi_ceiling {
+            return Some("io_pressure");
+        }
+        let cpu_avg10 = self.cpu_monitor.read_state().map_or(0.0, |d| d.some.avg10);
+        let cpu_ceiling = if is_emergency {
+            tunables.emergency_cpu_psi_ceiling
+        } else {
+            tunables.cpu_psi_ceiling
+        };
+        (cpu_avg10 > cpu_ceiling).then_some("cpu_pressure")
+    }
+    fn supervise(&mut self, now: time::Instant) {
+        let Some(reason) = self.current_blocker(self.is_emergency) else {
+            if self.blocked_since.take().is_some() {
+                log::info!("CleanerController: Pressure cleared, resuming sweep.");
+                self.gate
+                    .paused
+                    .store(false, sync::atomic::Ordering::Release);
+            }
+            return;
+        };
+        let since = *self.blocked_since.get_or_insert_with(|| {
+            log::info!("CleanerController: Pausing sweep ({reason}).");
+            now
+        });
+        self.gate
+            .paused
+            .store(true, sync::atomic::Ordering::Release);
+        if now.duration_since(since) >= self.tunables.pause_budget && !self.gate.is_stopped() {
+            log::info!("CleanerController: Still blocked by {reason}, stopping sweep.");
+            self.gate.stop.store(true, sync::atomic::Ordering::Release);
+        }
+    }
+    fn dispatch(&mut self, now: time::Instant, is_emergency: bool) {
+        self.gate.stop.store(false, sync::atomic::Ordering::Release);
+        self.gate
+            .paused
+            .store(false, sync::atomic::Ordering::Release);
+        self.gate
+            .running
+            .store(true, sync::atomic::Ordering::Release);
+        self.is_emergency = is_emergency;
+        self.blocked_since = None;
+        let request = CleanRequest {
+            is_critical: is_emergency,
+            recent: self.foreground.recent().into_iter().collect(),
+        };
+        match self.tx.send(request) {
+            Ok(()) => self.last_sweep = now,
+            Err(e) => {
+                log::error!("CleanerController: Failed to signal: {e}");
+                self.gate
+                    .running
+                    .store(false, sync::atomic::Ordering::Release);
+            }
+        }
+    }
+}
+
+impl Drop for CleanerController {
+    fn drop(&mut self) {
+        self.gate.stop.store(true, sync::atomic::Ordering::Release);
+    }
 }
 
 impl traits::EventHandler for CleanerController {
@@ -304,45 +1058,38 @@ impl traits::EventHandler for CleanerController {
         _context: &mut state::DaemonContext,
     ) -> Result<traits::LoopAction, types::QosError> {
         let now = time::Instant::now();
-        if now.duration_since(self.last_sweep).as_millis() < self.tunables.sweep_interval_ms as u128
+        if now.duration_since(self.last_sample).as_millis()
+            >= self.tunables.foreground_sample_ms as u128
         {
-            return Ok(traits::LoopAction::Continue);
+            self.foreground
+                .sample(now, self.tunables.recent_foreground_window);
+            self.last_sample = now;
         }
-        let is_emergency = self.is_storage_critical();
-        let temp = self.thermal.read();
-        if is_emergency {
-            if temp > 46.0 {
-                return Ok(traits::LoopAction::Continue);
-            }
-        } else if temp > 40.0 {
+        if self.is_running() {
+            self.supervise(now);
             return Ok(traits::LoopAction::Continue);
         }
-        let io_busy = self
-            .io_monitor
-            .read_state()
-            .map(|d| d.some.avg10 > 3.0)
-            .unwrap_or(false);
-        if !is_emergency && io_busy {
+        let interval = if self.gate.resume.load(sync::atomic::Ordering::Acquire) {
+            self.tunables.resume_interval
+        } else {
+            time::Duration::from_millis(self.tunables.sweep_interval_ms as u64)
+        };
+        if now.duration_since(self.last_sweep) < interval {
             return Ok(traits::LoopAction::Continue);
         }
-        let cpu_stats_opt = self.cpu_monitor.read_state().ok();
-        let cpu_avg10 = cpu_stats_opt.as_ref().map_or(0.0, |d| d.some.avg10);
-        let cpu_busy = cpu_avg10 > 3.0;
-        if is_emergency {
-            if cpu_busy && cpu_avg10 > 80.0 {
-                return Ok(traits::LoopAction::Continue);
-            }
-        } else if cpu_busy {
+        let is_emergency = self.is_storage_critical();
+        if self.current_blocker(is_emergency).is_some() {
             return Ok(traits::LoopAction::Continue);
         }
-        match self.tx.send(()) {
-            Ok(()) => self.last_sweep = now,
-            Err(e) => log::error!("CleanerController: Failed to signal: {e}"),
-        }
+        self.dispatch(now, is_emergency);
         Ok(traits::LoopAction::Continue)
     }
     fn get_timeout_ms(&self) -> i32 {
-        self.tunables.sweep_interval_ms
+        if self.is_running() {
+            self.tunables.active_interval_ms
+        } else {
+            self.tunables.foreground_sample_ms
+        }
     }
     fn get_poll_flags(&self) -> rustix::event::epoll::EventFlags {
         rustix::event::epoll::EventFlags::empty()
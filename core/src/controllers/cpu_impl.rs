@@ -1,15 +1,29 @@
 //! Author: [Seclususs](https://github.com/seclususs)
 
-use crate::algorithms::{cpu_math, poll_math, thermal_math};
-use crate::config::{kernel_limits, loop_settings};
-use crate::daemon::{state, traits, types};
+use crate::algorithms::{cpu_math, poll_math, thermal_math, thermal_tuning};
+use crate::config::{kernel_limits, loop_settings, thermal_profile};
+use crate::daemon::{state, status, traits, types};
 use crate::hal::{battery, filesystem, kernel, thermal};
-use crate::monitors::psi_monitor;
-use crate::resources::sys_paths;
+use crate::monitors::{psi_monitor, stat_monitor};
+use crate::resources::{discovery, sys_paths};
 use crate::utils::{cached_file, math};
 
 use std::{fs, io, os, time};
 
+fn probe_run_to_parity() -> Option<bool> {
+    let features = fs::read_to_string(sys_paths::K_SCHED_FEATURES).ok()?;
+    let enabled = features.split_whitespace().any(|f| f == "RUN_TO_PARITY");
+    let disabled = features.split_whitespace().any(|f| f == "NO_RUN_TO_PARITY");
+    (enabled || disabled).then_some(enabled)
+}
+
+fn open_cgroup_knob(group: &str, knob: &str, initial_value: u64) -> cached_file::CachedFile {
+    cached_file::CachedFile::new_opt(
+        filesystem::open_file_for_write(&format!("{group}/{knob}")).ok(),
+        initial_value,
+    )
+}
+
 pub struct CpuController {
     fd: fs::File,
     latency: cached_file::CachedFile,
@@ -18,21 +32,38 @@ pub struct CpuController {
     migration: cached_file::CachedFile,
     walt_init: cached_file::CachedFile,
     uclamp_min: cached_file::CachedFile,
+    base_slice: cached_file::CachedFile,
+    run_to_parity: Option<bool>,
+    scheduler: discovery::SchedulerProfile,
+    top_app_uclamp_min: cached_file::CachedFile,
+    foreground_uclamp_min: cached_file::CachedFile,
+    background_uclamp_max: cached_file::CachedFile,
+    system_bg_uclamp_max: cached_file::CachedFile,
+    cgroup_uclamp_active: bool,
     psi_cpu: psi_monitor::PsiMonitor,
+    stat_monitor: Option<stat_monitor::StatMonitor>,
     thermal_manager: thermal_math::ThermalManager,
     thermal_config: thermal_math::ThermalConfig,
-    cpu_sensor: thermal::ThermalSensor,
-    battery_sensor: thermal::ThermalSensor,
+    thermal_tuner: thermal_tuning::ThermalTuner,
+    cpu_sensor: thermal::GuardedThermalSensor,
+    zone_set: thermal::ThermalZoneSet,
+    cached_cpu_temp: f32,
+    last_zone_scan: time::Instant,
+    battery_sensor: thermal::GuardedThermalSensor,
     battery_capacity_sensor: battery::BatterySensor,
     cached_bat_level: f32,
     cached_bat_temp: f32,
     last_bat_check: time::Instant,
+    power_monitor: Option<battery::PowerMonitor>,
+    last_power_check: time::Instant,
     current_latency: f32,
     current_min_gran: f32,
     current_wakeup: f32,
     current_migration: f32,
     current_walt_init: f32,
     current_uclamp_min: f32,
+    current_base_slice: f32,
+    current_cgroup_uclamp: cpu_math::CgroupUclampTargets,
     load_state: cpu_math::LoadState,
     cpu_math_config: cpu_math::CpuMathConfig,
     cpu_kernel_limits: cpu_math::CpuKernelLimits,
@@ -42,6 +73,7 @@ pub struct CpuController {
 }
 
 impl CpuController {
+    #[allow(clippy::too_many_lines)]
     pub fn new() -> Result<Self, types::QosError> {
         log::info!("CpuController: Initializing...");
         let config_limits = kernel_limits::GlobalConfig::default().cpu_config;
@@ -59,41 +91,107 @@ impl CpuController {
             max_walt_init_pct: config_limits.max_walt_init_pct as f32,
             min_uclamp_min: config_limits.min_uclamp_min as f32,
             max_uclamp_min: config_limits.max_uclamp_min as f32,
+            min_bg_uclamp_max: config_limits.min_bg_uclamp_max as f32,
+            max_bg_uclamp_max: config_limits.max_bg_uclamp_max as f32,
+            min_base_slice_ns: config_limits.min_base_slice_ns as f32,
+            max_base_slice_ns: config_limits.max_base_slice_ns as f32,
         };
+        let scheduler = sys_paths::get_scheduler_profile();
+        log::info!(
+            "CpuController: Scheduler profile {:?}/{:?}",
+            scheduler.fair,
+            scheduler.tracking
+        );
+        let is_eevdf = scheduler.fair == discovery::FairScheduler::Eevdf;
         let raw_fd = kernel::register_psi_trigger(sys_paths::K_PSI_CPU_PATH, 100_000, 1_000_000)
             .map_err(|e| types::QosError::FfiError(format!("CPU Trigger Error: {e}")))?;
         let fd = unsafe { os::fd::FromRawFd::from_raw_fd(raw_fd) };
-        let latency = cached_file::CachedFile::new_opt(
-            filesystem::open_file_for_write(sys_paths::K_SCHED_LATENCY_NS).ok(),
-            0,
-        );
-        let min_gran = cached_file::CachedFile::new_opt(
-            filesystem::open_file_for_write(sys_paths::K_SCHED_MIN_GRANULARITY_NS).ok(),
-            0,
-        );
-        let wakeup = cached_file::CachedFile::new_opt(
-            filesystem::open_file_for_write(sys_paths::K_SCHED_WAKEUP_GRANULARITY_NS).ok(),
+        let open_cfs = |path: &str| {
+            if is_eevdf {
+                None
+            } else {
+                filesystem::open_file_for_write(path).ok()
+            }
+        };
+        let latency = cached_file::CachedFile::new_opt(open_cfs(sys_paths::K_SCHED_LATENCY_NS), 0);
+        let min_gran =
+            cached_file::CachedFile::new_opt(open_cfs(sys_paths::K_SCHED_MIN_GRANULARITY_NS), 0);
+        let wakeup =
+            cached_file::CachedFile::new_opt(open_cfs(sys_paths::K_SCHED_WAKEUP_GRANULARITY_NS), 0);
+        let base_slice = cached_file::CachedFile::new_opt(
+            if is_eevdf {
+                filesystem::open_first_for_write(sys_paths::K_SCHED_BASE_SLICE_CANDIDATES)
+            } else {
+                None
+            },
             0,
         );
+        let run_to_parity = if is_eevdf {
+            probe_run_to_parity()
+        } else {
+            None
+        };
         let migration = cached_file::CachedFile::new_opt(
-            filesystem::open_file_for_write(sys_paths::K_SCHED_MIGRATION_COST_NS).ok(),
+            filesystem::open_first_for_write(sys_paths::K_SCHED_MIGRATION_COST_CANDIDATES),
             0,
         );
         let walt_init = cached_file::CachedFile::new_opt(
-            filesystem::open_file_for_write(sys_paths::K_SCHED_WALT_INIT_TASK_LOAD_PCT).ok(),
+            if scheduler.tracking == discovery::LoadTracking::Walt {
+                filesystem::open_first_for_write(sys_paths::K_SCHED_WALT_INIT_CANDIDATES)
+            } else {
+                None
+            },
             config_limits.min_walt_init_pct,
         );
         let uclamp_min = cached_file::CachedFile::new_opt(
             filesystem::open_file_for_write(sys_paths::K_SCHED_UCLAMP_UTIL_MIN).ok(),
             config_limits.min_uclamp_min,
         );
+        let top_app_uclamp_min = open_cgroup_knob(
+            sys_paths::K_CPUCTL_TOP_APP,
+            sys_paths::K_CGROUP_UCLAMP_MIN,
+            0,
+        );
+        let foreground_uclamp_min = open_cgroup_knob(
+            sys_paths::K_CPUCTL_FOREGROUND,
+            sys_paths::K_CGROUP_UCLAMP_MIN,
+            0,
+        );
+        let background_uclamp_max = open_cgroup_knob(
+            sys_paths::K_CPUCTL_BACKGROUND,
+            sys_paths::K_CGROUP_UCLAMP_MAX,
+            config_limits.max_bg_uclamp_max,
+        );
+        let system_bg_uclamp_max = open_cgroup_knob(
+            sys_paths::K_CPUCTL_SYSTEM_BACKGROUND,
+            sys_paths::K_CGROUP_UCLAMP_MAX,
+            config_limits.max_bg_uclamp_max,
+        );
+        let cgroup_uclamp_active = top_app_uclamp_min.is_active()
+            || background_uclamp_max.is_active()
+            || system_bg_uclamp_max.is_active();
+        if cgroup_uclamp_active {
+            log::info!("CpuController: Per-cgroup uclamp control enabled.");
+        }
         let psi_cpu = psi_monitor::PsiMonitor::new(sys_paths::K_PSI_CPU_PATH)?;
-        let cpu_path = sys_paths::get_cpu_temp_path();
-        let cpu_sensor = thermal::ThermalSensor::new(cpu_path.to_str().unwrap_or_default(), 70.0);
-        let battery_sensor = thermal::ThermalSensor::new(sys_paths::K_BATTERY_TEMP_PATH, 35.0);
+        let stat_monitor = stat_monitor::StatMonitor::new(sys_paths::K_PROC_STAT_PATH).ok();
+        let cpu_sensor =
+            thermal::GuardedThermalSensor::new(sys_paths::get_cpu_temp_candidates(), 70.0);
+        let zone_set = thermal::ThermalZoneSet::new(sys_paths::get_thermal_zones());
+        let battery_sensor =
+            thermal::GuardedThermalSensor::new(sys_paths::get_battery_temp_candidates(), 35.0);
         let battery_capacity_sensor =
             battery::BatterySensor::new(sys_paths::K_BATTERY_CAPACITY_PATH);
-        let thermal_config = thermal_math::ThermalConfig::default();
+        let mut thermal_config = thermal_math::ThermalConfig::default();
+        let thermal_tuner = thermal_tuning::ThermalTuner::new(thermal_config);
+        if let Some(gains) = thermal_profile::load(&thermal_config) {
+            log::info!("CpuController: Applying stored thermal profile.");
+            gains.apply(&mut thermal_config);
+            status::publish(
+                "thermal_tuning",
+                thermal_profile::describe(&gains, "profile"),
+            );
+        }
         let thermal_manager = thermal_math::ThermalManager::default();
         let poller = poll_math::AdaptivePoller::new(1.5, 0.05, poll_math::PollerConfig::default());
         let mut controller = Self {
@@ -104,21 +202,45 @@ impl CpuController {
             migration,
             walt_init,
             uclamp_min,
+            base_slice,
+            run_to_parity,
+            scheduler,
+            top_app_uclamp_min,
+            foreground_uclamp_min,
+            background_uclamp_max,
+            system_bg_uclamp_max,
+            cgroup_uclamp_active,
             psi_cpu,
+            stat_monitor,
             thermal_manager,
             thermal_config,
+            thermal_tuner,
             cpu_sensor,
+            zone_set,
+            cached_cpu_temp: 35.0,
+            last_zone_scan: time::Instant::now()
+                .checked_sub(time::Duration::from_secs(1))
+                .unwrap_or_else(time::Instant::now),
             battery_sensor,
             battery_capacity_sensor,
             cached_bat_level: 50.0,
             cached_bat_temp: 35.0,
             last_bat_check: time::Instant::now(),
+            power_monitor: battery::PowerMonitor::new(),
+            last_power_check: time::Instant::now(),
             current_latency: config_limits.min_latency_ns as f32,
             current_min_gran: config_limits.min_granularity_ns as f32,
             current_wakeup: config_limits.min_wakeup_ns as f32,
             current_migration: config_limits.min_migration_cost as f32,
             current_walt_init: config_limits.min_walt_init_pct as f32,
             current_uclamp_min: config_limits.min_uclamp_min as f32,
+            current_base_slice: config_limits.min_base_slice_ns as f32,
+            current_cgroup_uclamp: cpu_math::CgroupUclampTargets {
+                top_app_min: 0.0,
+                foreground_min: 0.0,
+                background_max: config_limits.max_bg_uclamp_max as f32,
+                system_background_max: config_limits.max_bg_uclamp_max as f32,
+            },
             load_state: cpu_math::LoadState::default(),
             cpu_math_config,
             cpu_kernel_limits,
@@ -127,10 +249,11 @@ impl CpuController {
             next_wake_ms: loop_settings::MIN_POLLING_MS as i32,
         };
         controller.cached_bat_level = controller.battery_capacity_sensor.read();
-        controller.cached_bat_temp = controller.battery_sensor.read();
+        controller.cached_bat_temp = controller.battery_sensor.read(time::Instant::now());
         controller.apply_values(true);
         Ok(controller)
     }
+    #[allow(clippy::too_many_lines)]
     fn update_dynamics(
         &mut self,
         context: &mut state::DaemonContext,
@@ -140,18 +263,64 @@ impl CpuController {
         let io_psi = context.pressure.io_psi;
         let target_psi = some_cpu.current;
         let is_break = some_cpu.nis > self.cpu_math_config.nis_threshold;
-        let cpu_temp = self.cpu_sensor.read();
+        let utilization = self
+            .stat_monitor
+            .as_mut()
+            .and_then(|m| m.read_state().ok())
+            .unwrap_or_default();
+        context.pressure.cpu_util = utilization.aggregate;
+        context.pressure.cpu_peak_core = utilization.peak_core;
+        context.pressure.cpu_iowait = utilization.iowait;
+        context.pressure.cpu_irq = utilization.irq_share;
+        context.pressure.cpu_online = utilization.online_cpus;
+        context.cpu_cores.clear();
+        if let Some(monitor) = self.stat_monitor.as_ref() {
+            context.cpu_cores.extend_from_slice(monitor.per_core());
+        }
         let now = time::Instant::now();
+        let cpu_temp = self.read_process_temp(now, context);
         if now.duration_since(self.last_bat_check).as_secs() >= 5 {
             self.cached_bat_level = self.battery_capacity_sensor.read();
-            self.cached_bat_temp = self.battery_sensor.read();
+            self.cached_bat_temp = self.battery_sensor.read(now);
             self.last_bat_check = now;
         }
+        if let Some(monitor) = self.power_monitor.as_mut()
+            && now.duration_since(self.last_power_check).as_millis() >= 1000
+        {
+            context.power = monitor.sample();
+            self.last_power_check = now;
+        }
         let bat_level = self.cached_bat_level;
         let bat_temp = self.cached_bat_temp;
-        let thermal_scale =
+        let pid_scale =
             self.thermal_manager
                 .update(cpu_temp, bat_temp, target_psi, &self.thermal_config);
+        let forecast = self.thermal_manager.forecast();
+        if let Some(gains) =
+            self.thermal_tuner
+                .observe(now, utilization.aggregate, cpu_temp, pid_scale)
+        {
+            log::info!(
+                "CpuController: Thermal auto-tune Kp={:.3} Ki={:.4} Kd={:.3} tau={:.1}s delay={:.1}s",
+                gains.kp_base,
+                gains.ki_base,
+                gains.kd_fast,
+                gains.smith_tau,
+                gains.smith_delay_sec
+            );
+            gains.apply(&mut self.thermal_config);
+            if let Err(e) = thermal_profile::save(&gains) {
+                log::warn!("CpuController: Failed to persist thermal profile: {e}");
+            }
+            status::publish(
+                "thermal_tuning",
+                thermal_profile::describe(&gains, "identified"),
+            );
+        }
+        let thermal_scale = pid_scale.min(thermal_math::calculate_forecast_scale(
+            &forecast,
+            &self.thermal_config,
+        ));
         let trend_factor = cpu_math::calculate_trend_gain(some_cpu.velocity);
         let dt_duration = now.duration_since(self.last_tick);
         self.last_tick = now;
@@ -160,6 +329,7 @@ impl CpuController {
         let (integral_total, integral_dot) = cpu_math::update_integral_params(
             &mut self.load_state,
             bat_level,
+            context.power.discharge_mw(),
             dt_safe,
             &self.cpu_math_config,
         );
@@ -173,6 +343,8 @@ impl CpuController {
             integral_total,
             integral_dot,
             is_structural_break: is_break,
+            utilization: utilization.aggregate,
+            peak_core_utilization: utilization.peak_core,
         };
         let load_demand = cpu_math::calculate_load_demand(
             &mut self.load_state,
@@ -186,6 +358,9 @@ impl CpuController {
             &self.cpu_math_config,
         );
         context.pressure.cpu_psi = p_eff;
+        context.thermal.scale = thermal_scale;
+        context.thermal.cpu_temp = cpu_temp;
+        context.thermal.forecast = forecast;
         let mut calculated_poll =
             self.poller
                 .calculate_next_interval(p_eff, some_cpu.avg300, some_cpu.velocity)
@@ -218,12 +393,19 @@ impl CpuController {
             &self.cpu_math_config,
             &self.cpu_kernel_limits,
         );
+        let target_cgroup_uclamp = cpu_math::calculate_cgroup_uclamp(
+            target_uclamp,
+            thermal_scale,
+            &self.cpu_kernel_limits,
+        );
         self.current_latency = target_latency;
         self.current_min_gran = target_min_gran;
         self.current_wakeup = target_wakeup;
         self.current_migration = target_migration;
         self.current_walt_init = target_walt_init;
         self.current_uclamp_min = target_uclamp;
+        self.current_cgroup_uclamp = target_cgroup_uclamp;
+        self.update_eevdf_targets(target_min_gran, target_wakeup);
         self.apply_values(false);
         Ok(())
     }
@@ -256,8 +438,18 @@ impl CpuController {
             self.current_uclamp_min,
             self.cpu_kernel_limits.min_uclamp_min as u64,
         );
+        let slice_u64 = math::sanitize_to_clean_u64(
+            self.current_base_slice,
+            self.cpu_kernel_limits.min_base_slice_ns as u64,
+            50_000,
+        );
         self.latency
             .update(lat_u64, force, &cached_file::CheckStrategy::Relative(0.10));
+        self.base_slice.update(
+            slice_u64,
+            force,
+            &cached_file::CheckStrategy::Relative(0.10),
+        );
         self.min_gran
             .update(gran_u64, force, &cached_file::CheckStrategy::Relative(0.10));
         self.wakeup
@@ -266,8 +458,86 @@ impl CpuController {
             .update(mig_u64, force, &cached_file::CheckStrategy::Absolute(50000));
         self.walt_init
             .update(walt_u64, force, &cached_file::CheckStrategy::Absolute(5));
-        self.uclamp_min
-            .update(uclamp_u64, force, &cached_file::CheckStrategy::Absolute(32));
+        if self.cgroup_uclamp_active {
+            self.apply_cgroup_uclamp(force);
+        } else {
+            self.uclamp_min
+                .update(uclamp_u64, force, &cached_file::CheckStrategy::Absolute(32));
+        }
+    }
+    fn read_process_temp(&mut self, now: time::Instant, context: &mut state::DaemonContext) -> f32 {
+        if self.zone_set.is_empty() {
+            let temp = self.cpu_sensor.read(now);
+            self.publish_sensor_health(context);
+            return temp;
+        }
+        if now.duration_since(self.last_zone_scan).as_millis() >= 1000 {
+            let fallback = self.cpu_sensor.read(now);
+            self.zone_set.read_into(&mut context.thermal_zones);
+            self.cached_cpu_temp =
+                thermal_math::aggregate_zone_temps(&context.thermal_zones, &self.thermal_config)
+                    .unwrap_or(fallback);
+            self.last_zone_scan = now;
+            self.publish_sensor_health(context);
+        }
+        self.cached_cpu_temp
+    }
+    fn publish_sensor_health(&self, context: &mut state::DaemonContext) {
+        context.thermal.sensor_health = self.cpu_sensor.health();
+        context.thermal.sensor_path = self.cpu_sensor.active_path();
+        context.thermal.sensor_failovers = self.cpu_sensor.failovers();
+    }
+    fn update_eevdf_targets(&mut self, target_min_gran: f32, target_wakeup: f32) {
+        if self.scheduler.fair != discovery::FairScheduler::Eevdf {
+            return;
+        }
+        self.current_base_slice =
+            cpu_math::calculate_base_slice(target_min_gran, &self.cpu_kernel_limits);
+        if let Some(current) = self.run_to_parity {
+            let next =
+                cpu_math::resolve_run_to_parity(target_wakeup, current, &self.cpu_kernel_limits);
+            if next != current {
+                self.set_run_to_parity(next);
+            }
+        }
+    }
+    fn set_run_to_parity(&mut self, enabled: bool) {
+        let feature = if enabled {
+            "RUN_TO_PARITY"
+        } else {
+            "NO_RUN_TO_PARITY"
+        };
+        match filesystem::write_to_file(sys_paths::K_SCHED_FEATURES, feature) {
+            Ok(()) => self.run_to_parity = Some(enabled),
+            Err(e) => {
+                log::debug!("CpuController: Failed to set {feature}: {e}");
+                self.run_to_parity = None;
+            }
+        }
+    }
+    fn apply_cgroup_uclamp(&mut self, force: bool) {
+        let targets = self.current_cgroup_uclamp;
+        let bg_fallback = self.cpu_kernel_limits.max_bg_uclamp_max as u64;
+        self.top_app_uclamp_min.update(
+            math::sanitize_to_u64(targets.top_app_min, 0),
+            force,
+            &cached_file::CheckStrategy::Absolute(3),
+        );
+        self.foreground_uclamp_min.update(
+            math::sanitize_to_u64(targets.foreground_min, 0),
+            force,
+            &cached_file::CheckStrategy::Absolute(3),
+        );
+        self.background_uclamp_max.update(
+            math::sanitize_to_u64(targets.background_max, bg_fallback),
+            force,
+            &cached_file::CheckStrategy::Absolute(5),
+        );
+        self.system_bg_uclamp_max.update(
+            math::sanitize_to_u64(targets.system_background_max, bg_fallback),
+            force,
+            &cached_file::CheckStrategy::Absolute(5),
+        );
     }
 }
 
@@ -0,0 +1,227 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::daemon::{state, traits, types};
+use crate::hal::filesystem;
+use crate::resources::sys_paths;
+use crate::utils::strings;
+
+use std::{fs, os, time};
+
+#[derive(Debug, Clone, Copy)]
+struct CpusetConfig {
+    interval_ms: i32,
+    pressure_high: f32,
+    hysteresis: f32,
+    min_dwell: time::Duration,
+}
+
+impl Default for CpusetConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: 2000,
+            pressure_high: 40.0,
+            hysteresis: 0.15,
+            min_dwell: time::Duration::from_secs(5),
+        }
+    }
+}
+
+struct CpusetGroup {
+    path: &'static str,
+    original: String,
+    allowed: Vec<u32>,
+    current: String,
+}
+
+impl CpusetGroup {
+    fn open(path: &'static str) -> Option<Self> {
+        let original = fs::read_to_string(path).ok()?.trim().to_string();
+        if original.is_empty() {
+            return None;
+        }
+        Some(Self {
+            path,
+            allowed: strings::parse_cpu_list(&original),
+            current: original.clone(),
+            original,
+        })
+    }
+    fn apply_cpus(&mut self, cpus: &[u32]) {
+        let permitted: Vec<u32> = cpus
+            .iter()
+            .copied()
+            .filter(|cpu| self.allowed.contains(cpu))
+            .collect();
+        if permitted.is_empty() {
+            self.restore();
+        } else {
+            self.apply(&strings::format_cpu_list(&permitted));
+        }
+    }
+    fn apply(&mut self, mask: &str) {
+        if self.current == mask {
+            return;
+        }
+        match filesystem::write_to_file(self.path, mask) {
+            Ok(()) => mask.clone_into(&mut self.current),
+            Err(e) => log::debug!("Cpuset: Failed to write '{mask}' -> {}: {e}", self.path),
+        }
+    }
+    fn restore(&mut self) {
+        let original = self.original.clone();
+        self.apply(&original);
+    }
+}
+
+pub struct CpusetController {
+    dummy_fd: rustix::fd::OwnedFd,
+    background: Option<CpusetGroup>,
+    system_background: Option<CpusetGroup>,
+    restricted: Option<CpusetGroup>,
+    cluster_masks: Vec<Vec<u32>>,
+    level: usize,
+    last_change: time::Instant,
+    tunables: CpusetConfig,
+}
+
+impl CpusetController {
+    pub fn new() -> Result<Self, types::QosError> {
+        log::info!("CpusetController: Initializing...");
+        let clusters = sys_paths::get_cpu_clusters();
+        if clusters.len() < 2 {
+            return Err(types::QosError::SystemCheckFailed(
+                "Cpuset control requires a multi-cluster topology.".to_string(),
+            ));
+        }
+        let mut allowed = Vec::new();
+        let mut cluster_masks = Vec::with_capacity(clusters.len());
+        for cluster in clusters {
+            allowed.extend_from_slice(&cluster.cpus);
+            cluster_masks.push(allowed.clone());
+        }
+        let background = CpusetGroup::open(sys_paths::K_CPUSET_BACKGROUND_CPUS);
+        let system_background = CpusetGroup::open(sys_paths::K_CPUSET_SYSTEM_BACKGROUND_CPUS);
+        let restricted = CpusetGroup::open(sys_paths::K_CPUSET_RESTRICTED_CPUS);
+        if background.is_none() && system_background.is_none() && restricted.is_none() {
+            return Err(types::QosError::SystemCheckFailed(
+                "No cpuset groups found.".to_string(),
+            ));
+        }
+        let dummy_fd = rustix::event::eventfd(
+            0,
+            rustix::event::EventfdFlags::CLOEXEC | rustix::event::EventfdFlags::NONBLOCK,
+        )
+        .map_err(|e| types::QosError::SystemCheckFailed(format!("Eventfd fail: {e}")))?;
+        let mut controller = Self {
+            dummy_fd,
+            background,
+            system_background,
+            restricted,
+            cluster_masks,
+            level: 0,
+            last_change: time::Instant::now(),
+            tunables: CpusetConfig::default(),
+        };
+        controller.level = controller.max_level();
+        controller.apply_level();
+        Ok(controller)
+    }
+    // The prime cluster stays reserved for foreground work only when there
+    // is a mid cluster to widen into; with two clusters the top level is the
+    // full mask.
+    fn max_level(&self) -> usize {
+        let clusters = self.cluster_masks.len();
+        if clusters >= 3 {
+            clusters - 2
+        } else {
+            clusters - 1
+        }
+    }
+    fn resolve_level(&self, context: &state::DaemonContext) -> usize {
+        let max_level = self.max_level();
+        let pressure_ratio =
+            (context.pressure.cpu_psi / self.tunables.pressure_high).clamp(0.0, 1.0);
+        let headroom = context.thermal.scale.clamp(0.0, 1.0) * (1.0 - pressure_ratio);
+        let position = headroom * max_level as f32;
+        let distance = (position - self.level as f32).abs();
+        if distance > 0.5 + self.tunables.hysteresis {
+            (position.round() as usize).min(max_level)
+        } else {
+            self.level
+        }
+    }
+    fn apply_level(&mut self) {
+        let max_level = self.max_level();
+        let bg_level = self.level;
+        let sys_bg_level = (bg_level + 1).min(max_level);
+        let restricted_level = bg_level.saturating_sub(1);
+        if let Some(group) = self.background.as_mut() {
+            group.apply_cpus(&self.cluster_masks[bg_level]);
+        }
+        if let Some(group) = self.system_background.as_mut() {
+            group.apply_cpus(&self.cluster_masks[sys_bg_level]);
+        }
+        if let Some(group) = self.restricted.as_mut() {
+            group.apply_cpus(&self.cluster_masks[restricted_level]);
+        }
+    }
+}
+
+impl Drop for CpusetController {
+    fn drop(&mut self) {
+        log::info!("CpusetController: Restoring original masks.");
+        for group in [
+            self.background.as_mut(),
+            self.system_background.as_mut(),
+            self.restricted.as_mut(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            group.restore();
+        }
+    }
+}
+
+impl traits::EventHandler for CpusetController {
+    fn as_raw_fd(&self) -> os::fd::RawFd {
+        os::fd::AsRawFd::as_raw_fd(&self.dummy_fd)
+    }
+    fn on_event(
+        &mut self,
+        _context: &mut state::DaemonContext,
+    ) -> Result<traits::LoopAction, types::QosError> {
+        let mut buf = [0u8; 8];
+        let _ = rustix::io::read(&self.dummy_fd, &mut buf);
+        Ok(traits::LoopAction::Continue)
+    }
+    fn on_timeout(
+        &mut self,
+        context: &mut state::DaemonContext,
+    ) -> Result<traits::LoopAction, types::QosError> {
+        let now = time::Instant::now();
+        if now.duration_since(self.last_change) < self.tunables.min_dwell {
+            return Ok(traits::LoopAction::Continue);
+        }
+        let next_level = self.resolve_level(context);
+        if next_level != self.level {
+            log::debug!(
+                "Cpuset: Background level {} -> {} (psi {:.1}, thermal {:.2})",
+                self.level,
+                next_level,
+                context.pressure.cpu_psi,
+                context.thermal.scale
+            );
+            self.level = next_level;
+            self.last_change = now;
+            self.apply_level();
+        }
+        Ok(traits::LoopAction::Continue)
+    }
+    fn get_timeout_ms(&self) -> i32 {
+        self.tunables.interval_ms
+    }
+    fn get_poll_flags(&self) -> rustix::event::epoll::EventFlags {
+        rustix::event::epoll::EventFlags::empty()
+    }
+}
@@ -0,0 +1,328 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::daemon::{state, status, traits, types};
+use crate::hal::{filesystem, kernel};
+use crate::monitors::{idle_monitor, psi_monitor};
+use crate::resources::{discovery, sys_paths};
+
+use std::fmt::Write;
+use std::{fs, io, os, time};
+
+// (knob, urgent value, kernel default). Sessions end by writing the kernel
+// default rather than a captured value, so an override left behind by a
+// killed daemon is never mistaken for the original.
+const URGENT_OVERRIDES: &[(&str, &str, &str)] = &[
+    ("gc_urgent", "1", "0"),
+    ("gc_urgent_sleep_time", "50", "500"),
+    ("discard_idle_interval", "0", "5"),
+];
+
+#[derive(Debug, Clone, Copy)]
+struct F2fsConfig {
+    idle_interval_ms: i32,
+    active_interval_ms: i32,
+    temp_ceiling: f32,
+    io_psi_ceiling: f32,
+    start_dirty_fraction: f32,
+    stop_dirty_fraction: f32,
+    max_session: time::Duration,
+    preempt_backoff: time::Duration,
+}
+
+impl Default for F2fsConfig {
+    fn default() -> Self {
+        Self {
+            idle_interval_ms: 60_000,
+            active_interval_ms: 5000,
+            temp_ceiling: 36.0,
+            io_psi_ceiling: 10.0,
+            start_dirty_fraction: 0.20,
+            stop_dirty_fraction: 0.10,
+            max_session: time::Duration::from_mins(30),
+            preempt_backoff: time::Duration::from_mins(10),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct F2fsStats {
+    dirty_segments: u64,
+    free_segments: u64,
+    gc_urgent: u64,
+    undiscard_blks: u64,
+}
+
+impl F2fsStats {
+    fn dirty_fraction(&self) -> f32 {
+        let total = self.dirty_segments + self.free_segments;
+        if total == 0 {
+            return 0.0;
+        }
+        self.dirty_segments as f32 / total as f32
+    }
+}
+
+struct KnobOverride {
+    path: String,
+    default: &'static str,
+}
+
+struct F2fsState {
+    device: &'static discovery::F2fsDevice,
+    stats: F2fsStats,
+    overrides: Vec<KnobOverride>,
+    session_start: Option<time::Instant>,
+}
+
+impl F2fsState {
+    fn new(device: &'static discovery::F2fsDevice) -> Self {
+        Self {
+            device,
+            stats: F2fsStats::default(),
+            overrides: Vec::new(),
+            session_start: None,
+        }
+    }
+    fn read_attr(&self, attr: &str) -> Option<u64> {
+        fs::read_to_string(self.device.attr_path(attr))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+    fn refresh(&mut self) -> bool {
+        let (Some(dirty_segments), Some(free_segments)) = (
+            self.read_attr("dirty_segments"),
+            self.read_attr("free_segments"),
+        ) else {
+            return false;
+        };
+        self.stats = F2fsStats {
+            dirty_segments,
+            free_segments,
+            gc_urgent: self.read_attr("gc_urgent").unwrap_or(0),
+            undiscard_blks: self.read_attr("undiscard_blks").unwrap_or(0),
+        };
+        true
+    }
+    fn is_active(&self) -> bool {
+        self.session_start.is_some()
+    }
+    fn reset_leftover(&mut self) {
+        if self.stats.gc_urgent == 0 {
+            return;
+        }
+        log::warn!(
+            "F2fsController: {} left in urgent GC, restoring defaults",
+            self.device.name
+        );
+        for &(attr, _, default) in URGENT_OVERRIDES {
+            if let Some(path) = self.device.attr_path(attr).to_str()
+                && fs::metadata(path).is_ok()
+                && let Err(e) = filesystem::write_to_file(path, default)
+            {
+                log::warn!("F2fsController: Failed to reset {path}: {e}");
+            }
+        }
+        self.stats.gc_urgent = self.read_attr("gc_urgent").unwrap_or(0);
+    }
+    fn start(&mut self, now: time::Instant) {
+        for &(attr, value, default) in URGENT_OVERRIDES {
+            let Some(path) = self.device.attr_path(attr).to_str().map(str::to_string) else {
+                continue;
+            };
+            if fs::metadata(&path).is_err() {
+                continue;
+            }
+            if filesystem::write_to_file(&path, value).is_ok() {
+                self.overrides.push(KnobOverride { path, default });
+            }
+        }
+        if self.overrides.is_empty() {
+            log::debug!(
+                "F2fsController: {} has no urgent GC knobs",
+                self.device.name
+            );
+            return;
+        }
+        log::info!(
+            "F2fsController: Urgent GC started on {} (dirty={} free={})",
+            self.device.name,
+            self.stats.dirty_segments,
+            self.stats.free_segments
+        );
+        self.session_start = Some(now);
+    }
+    fn stop(&mut self, reason: &str) {
+        for knob in self.overrides.drain(..).rev() {
+            if let Err(e) = filesystem::write_to_file(&knob.path, knob.default) {
+                log::warn!("F2fsController: Failed to restore {}: {e}", knob.path);
+            }
+        }
+        if let Some(start) = self.session_start.take() {
+            log::info!(
+                "F2fsController: Urgent GC stopped on {} after {} s ({reason})",
+                self.device.name,
+                start.elapsed().as_secs()
+            );
+        }
+    }
+}
+
+pub struct F2fsController {
+    psi_fd: fs::File,
+    io_psi: psi_monitor::PsiMonitor,
+    idle: idle_monitor::IdleMonitor,
+    devices: Vec<F2fsState>,
+    tunables: F2fsConfig,
+    backoff_until: Option<time::Instant>,
+    blocker: &'static str,
+}
+
+impl F2fsController {
+    pub fn new() -> Result<Self, types::QosError> {
+        log::info!("F2fsController: Initializing...");
+        let mut devices: Vec<F2fsState> = sys_paths::get_f2fs_devices()
+            .iter()
+            .map(F2fsState::new)
+            .collect();
+        devices.retain_mut(F2fsState::refresh);
+        if devices.is_empty() {
+            return Err(types::QosError::SystemCheckFailed(
+                "No f2fs filesystems found.".to_string(),
+            ));
+        }
+        for state in &mut devices {
+            state.reset_leftover();
+            log::info!(
+                "F2fsController: Managing {} ({})",
+                state.device.name,
+                state.device.mount_point.as_deref().unwrap_or("unmounted")
+            );
+        }
+        let raw_fd = kernel::register_psi_trigger(sys_paths::K_PSI_IO_PATH, 150_000, 1_000_000)
+            .map_err(|e| types::QosError::FfiError(format!("F2fs PSI Error: {e}")))?;
+        let psi_fd = unsafe { os::fd::FromRawFd::from_raw_fd(raw_fd) };
+        Ok(Self {
+            psi_fd,
+            io_psi: psi_monitor::PsiMonitor::new(sys_paths::K_PSI_IO_PATH)?,
+            idle: idle_monitor::IdleMonitor::new(),
+            devices,
+            tunables: F2fsConfig::default(),
+            backoff_until: None,
+            blocker: "startup",
+        })
+    }
+    fn is_active(&self) -> bool {
+        self.devices.iter().any(F2fsState::is_active)
+    }
+    fn stop_all(&mut self, reason: &str) {
+        for state in &mut self.devices {
+            state.stop(reason);
+        }
+    }
+    fn preempt(&mut self) {
+        self.stop_all("io pressure");
+        self.backoff_until = Some(time::Instant::now() + self.tunables.preempt_backoff);
+        self.blocker = "io_pressure";
+    }
+    fn evaluate(&mut self) {
+        let now = time::Instant::now();
+        let conditions = self.idle.sample();
+        let io_psi = self.io_psi.read_state().map_or(0.0, |d| d.some.current);
+        let blocker = if let Some(reason) = conditions.blocker(self.tunables.temp_ceiling) {
+            Some(reason)
+        } else if self.backoff_until.is_some_and(|until| now < until) {
+            Some("backoff")
+        } else if io_psi > self.tunables.io_psi_ceiling {
+            Some("io_pressure")
+        } else {
+            None
+        };
+        self.blocker = blocker.unwrap_or("none");
+        for state in &mut self.devices {
+            if !state.refresh() {
+                state.stop("stats unavailable");
+                continue;
+            }
+            let fraction = state.stats.dirty_fraction();
+            if let Some(reason) = blocker {
+                state.stop(reason);
+            } else if let Some(start) = state.session_start {
+                if fraction <= self.tunables.stop_dirty_fraction {
+                    state.stop("clean");
+                } else if now.duration_since(start) >= self.tunables.max_session {
+                    state.stop("session limit");
+                }
+            } else if fraction >= self.tunables.start_dirty_fraction {
+                state.start(now);
+            }
+        }
+        self.publish();
+    }
+    fn publish(&self) {
+        let mut body = String::with_capacity(96 * (self.devices.len() + 1));
+        let _ = writeln!(
+            body,
+            "state={} blocker={}",
+            if self.is_active() { "gc" } else { "idle" },
+            self.blocker
+        );
+        for state in &self.devices {
+            let stats = &state.stats;
+            let _ = writeln!(
+                body,
+                "{} mount={} dirty={} free={} dirty_frac={:.2} gc_urgent={} undiscard_blks={}",
+                state.device.name,
+                state.device.mount_point.as_deref().unwrap_or("-"),
+                stats.dirty_segments,
+                stats.free_segments,
+                stats.dirty_fraction(),
+                stats.gc_urgent,
+                stats.undiscard_blks
+            );
+        }
+        status::publish("f2fs", body);
+    }
+}
+
+impl Drop for F2fsController {
+    fn drop(&mut self) {
+        self.stop_all("shutdown");
+    }
+}
+
+impl traits::EventHandler for F2fsController {
+    fn as_raw_fd(&self) -> os::fd::RawFd {
+        os::fd::AsRawFd::as_raw_fd(&self.psi_fd)
+    }
+    fn on_event(
+        &mut self,
+        _context: &mut state::DaemonContext,
+    ) -> Result<traits::LoopAction, types::QosError> {
+        let mut buf = [0u8; 8];
+        let _ = io::Read::read(&mut self.psi_fd, &mut buf);
+        if self.is_active() {
+            self.preempt();
+            self.publish();
+        }
+        Ok(traits::LoopAction::Continue)
+    }
+    fn on_timeout(
+        &mut self,
+        _context: &mut state::DaemonContext,
+    ) -> Result<traits::LoopAction, types::QosError> {
+        self.evaluate();
+        Ok(traits::LoopAction::Continue)
+    }
+    fn get_timeout_ms(&self) -> i32 {
+        if self.is_active() {
+            self.tunables.active_interval_ms
+        } else {
+            self.tunables.idle_interval_ms
+        }
+    }
+    fn get_poll_flags(&self) -> rustix::event::epoll::EventFlags {
+        rustix::event::epoll::EventFlags::PRI | rustix::event::epoll::EventFlags::ERR
+    }
+}
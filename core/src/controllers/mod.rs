@@ -1,5 +1,11 @@
 pub mod blocker_impl;
+pub mod charging_impl;
 pub mod cleaner_impl;
 pub mod cpu_impl;
+pub mod cpuset_impl;
+pub mod f2fs_impl;
 pub mod signal_impl;
+pub mod status_impl;
 pub mod storage_impl;
+pub mod trim_impl;
+pub mod zram_impl;
@@ -0,0 +1,65 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::daemon::{state, status, traits, types};
+use crate::hal::filesystem;
+use crate::resources::sys_paths;
+
+use std::os;
+
+const STATUS_INTERVAL_MS: i32 = 10_000;
+
+pub struct StatusController {
+    dummy_fd: rustix::fd::OwnedFd,
+    write_failed: bool,
+}
+
+impl StatusController {
+    pub fn new() -> Result<Self, types::QosError> {
+        log::info!("StatusController: Initializing...");
+        let dummy_fd = rustix::event::eventfd(
+            0,
+            rustix::event::EventfdFlags::CLOEXEC | rustix::event::EventfdFlags::NONBLOCK,
+        )
+        .map_err(|e| types::QosError::SystemCheckFailed(format!("Eventfd fail: {e}")))?;
+        Ok(Self {
+            dummy_fd,
+            write_failed: false,
+        })
+    }
+}
+
+impl traits::EventHandler for StatusController {
+    fn as_raw_fd(&self) -> os::fd::RawFd {
+        os::fd::AsRawFd::as_raw_fd(&self.dummy_fd)
+    }
+    fn on_event(
+        &mut self,
+        _context: &mut state::DaemonContext,
+    ) -> Result<traits::LoopAction, types::QosError> {
+        let mut buf = [0u8; 8];
+        let _ = rustix::io::read(&self.dummy_fd, &mut buf);
+        Ok(traits::LoopAction::Continue)
+    }
+    fn on_timeout(
+        &mut self,
+        context: &mut state::DaemonContext,
+    ) -> Result<traits::LoopAction, types::QosError> {
+        let body = status::render(context);
+        match filesystem::write_state_file(sys_paths::K_STATUS_PATH, &body) {
+            Ok(()) => self.write_failed = false,
+            Err(e) => {
+                if !self.write_failed {
+                    log::warn!("StatusController: Failed to write status: {e}");
+                }
+                self.write_failed = true;
+            }
+        }
+        Ok(traits::LoopAction::Continue)
+    }
+    fn get_timeout_ms(&self) -> i32 {
+        STATUS_INTERVAL_MS
+    }
+    fn get_poll_flags(&self) -> rustix::event::epoll::EventFlags {
+        rustix::event::epoll::EventFlags::empty()
+    }
+}
@@ -1,177 +1,507 @@
 //! Author: [Seclususs](https://github.com/seclususs)
 
-use crate::algorithms::{poll_math, storage_math};
-use crate::config::{kernel_limits, loop_settings};
-use crate::daemon::{state, traits, types};
-use crate::hal::{filesystem, kernel};
+use crate::algorithms::{iosched_math, poll_math, storage_math};
+use crate::config::{kernel_limits, loop_settings, settings};
+use crate::daemon::{state, status, traits, types};
+use crate::hal::{battery, filesystem, kernel, screen};
 use crate::monitors::{disk_monitor, psi_monitor};
-use crate::resources::sys_paths;
+use crate::resources::{discovery, sys_paths};
 use crate::utils::{cached_file, math};
 
-use std::{fs, io, os, time};
+use std::fmt::Write;
+use std::{fs, io, os, path, time};
 
-pub struct StorageController {
-    fd: fs::File,
+const STATUS_INTERVAL: time::Duration = time::Duration::from_secs(5);
+const WRITEBACK_INTERVAL: time::Duration = time::Duration::from_secs(2);
+const SCREEN_INTERVAL: time::Duration = time::Duration::from_secs(1);
+const MAX_FAILURE_BACKOFF_MS: i32 = 60_000;
+
+fn open_knob(path: &path::Path, initial_value: u64) -> cached_file::CachedFile {
+    cached_file::CachedFile::new_opt(
+        path.to_str()
+            .and_then(|p| filesystem::open_file_for_write(p).ok()),
+        initial_value,
+    )
+}
+
+fn open_proc_knob(path: &str) -> (cached_file::CachedFile, Option<u64>) {
+    let original = fs::read_to_string(path)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok());
+    let knob = cached_file::CachedFile::new_opt(
+        filesystem::open_file_for_write(path).ok(),
+        original.unwrap_or(0),
+    );
+    (knob, original)
+}
+
+struct WritebackControl {
+    dirty_ratio: cached_file::CachedFile,
+    dirty_background_ratio: cached_file::CachedFile,
+    dirty_expire: cached_file::CachedFile,
+    dirty_writeback: cached_file::CachedFile,
+    originals: [Option<u64>; 4],
+    limits: storage_math::WritebackLimits,
+    mem_psi: Option<psi_monitor::PsiMonitor>,
+    charge: battery::ChargeStatusSensor,
+    targets: storage_math::WritebackTargets,
+    last_update: time::Instant,
+}
+
+impl WritebackControl {
+    fn open(config_limits: &kernel_limits::StorageKernelLimitsConfig) -> Option<Self> {
+        let (dirty_ratio, original_ratio) = open_proc_knob(sys_paths::K_VM_DIRTY_RATIO);
+        let (dirty_background_ratio, original_background) =
+            open_proc_knob(sys_paths::K_VM_DIRTY_BACKGROUND_RATIO);
+        let (dirty_expire, original_expire) = open_proc_knob(sys_paths::K_VM_DIRTY_EXPIRE_CS);
+        let (dirty_writeback, original_writeback) =
+            open_proc_knob(sys_paths::K_VM_DIRTY_WRITEBACK_CS);
+        if !dirty_ratio.is_active() && !dirty_expire.is_active() {
+            return None;
+        }
+        Some(Self {
+            dirty_ratio,
+            dirty_background_ratio,
+            dirty_expire,
+            dirty_writeback,
+            originals: [
+                original_ratio,
+                original_background,
+                original_expire,
+                original_writeback,
+            ],
+            limits: storage_math::WritebackLimits {
+                min_dirty_ratio: config_limits.min_dirty_ratio as f32,
+                max_dirty_ratio: config_limits.max_dirty_ratio as f32,
+                min_dirty_background_ratio: config_limits.min_dirty_background_ratio as f32,
+                max_dirty_background_ratio: config_limits.max_dirty_background_ratio as f32,
+                min_dirty_expire_cs: config_limits.min_dirty_expire_cs as f32,
+                max_dirty_expire_cs: config_limits.max_dirty_expire_cs as f32,
+                min_dirty_writeback_cs: config_limits.min_dirty_writeback_cs as f32,
+                max_dirty_writeback_cs: config_limits.max_dirty_writeback_cs as f32,
+            },
+            mem_psi: psi_monitor::PsiMonitor::new(sys_paths::K_PSI_MEM_PATH).ok(),
+            charge: battery::ChargeStatusSensor::new(),
+            targets: storage_math::WritebackTargets::default(),
+            last_update: time::Instant::now(),
+        })
+    }
+    fn update(
+        &mut self,
+        io_psi: f32,
+        write_kbps: f32,
+        screen_on: bool,
+        math_config: &storage_math::StorageMathConfig,
+        force: bool,
+    ) {
+        let now = time::Instant::now();
+        if !force && now.duration_since(self.last_update) < WRITEBACK_INTERVAL {
+            return;
+        }
+        self.last_update = now;
+        let mem_psi = self
+            .mem_psi
+            .as_mut()
+            .and_then(|m| m.read_state().ok())
+            .map_or(0.0, |d| d.some.current);
+        let input = storage_math::WritebackInput {
+            io_psi,
+            mem_psi,
+            write_kbps,
+            screen_on,
+            charging: self.charge.read().on_external_power(),
+        };
+        self.targets = storage_math::calculate_writeback_targets(input, math_config, &self.limits);
+        let ratio =
+            math::sanitize_to_u64(self.targets.dirty_ratio, self.limits.min_dirty_ratio as u64);
+        let background = math::sanitize_to_u64(
+            self.targets.dirty_background_ratio,
+            self.limits.min_dirty_background_ratio as u64,
+        );
+        let expire = math::sanitize_to_clean_u64(
+            self.targets.dirty_expire_cs,
+            self.limits.max_dirty_expire_cs as u64,
+            100,
+        );
+        let writeback = math::sanitize_to_clean_u64(
+            self.targets.dirty_writeback_cs,
+            self.limits.max_dirty_writeback_cs as u64,
+            50,
+        );
+        self.dirty_ratio
+            .update(ratio, force, &cached_file::CheckStrategy::Absolute(2));
+        self.dirty_background_ratio.update(
+            background,
+            force,
+            &cached_file::CheckStrategy::Absolute(1),
+        );
+        self.dirty_expire
+            .update(expire, force, &cached_file::CheckStrategy::Absolute(100));
+        self.dirty_writeback
+            .update(writeback, force, &cached_file::CheckStrategy::Absolute(50));
+    }
+}
+
+impl Drop for WritebackControl {
+    fn drop(&mut self) {
+        log::info!("Storage: Restoring vm dirty writeback knobs.");
+        let knobs = [
+            &mut self.dirty_ratio,
+            &mut self.dirty_background_ratio,
+            &mut self.dirty_expire,
+            &mut self.dirty_writeback,
+        ];
+        for (knob, original) in knobs.into_iter().zip(self.originals) {
+            if let Some(original) = original {
+                knob.update(original, true, &cached_file::CheckStrategy::Strict);
+            }
+        }
+    }
+}
+
+enum SchedulerKnobs {
+    Kyber {
+        read_lat: cached_file::CachedFile,
+        write_lat: cached_file::CachedFile,
+    },
+    Deadline {
+        read_expire: cached_file::CachedFile,
+        write_expire: cached_file::CachedFile,
+        fifo_batch: cached_file::CachedFile,
+    },
+    Bfq {
+        low_latency: cached_file::CachedFile,
+        enabled: bool,
+    },
+    Unmanaged,
+}
+
+impl SchedulerKnobs {
+    fn open(device: &discovery::BlockDevice) -> Self {
+        let knob = |name: &str| open_knob(&device.queue_path("iosched").join(name), 0);
+        let knobs = match device.active_scheduler().as_deref() {
+            Some("kyber") => Self::Kyber {
+                read_lat: knob("read_lat_nsec"),
+                write_lat: knob("write_lat_nsec"),
+            },
+            Some("mq-deadline" | "deadline") => Self::Deadline {
+                read_expire: knob("read_expire"),
+                write_expire: knob("write_expire"),
+                fifo_batch: knob("fifo_batch"),
+            },
+            Some("bfq") => Self::Bfq {
+                low_latency: knob("low_latency"),
+                enabled: true,
+            },
+            _ => Self::Unmanaged,
+        };
+        if knobs.is_active() {
+            knobs
+        } else {
+            Self::Unmanaged
+        }
+    }
+    fn is_active(&self) -> bool {
+        match self {
+            Self::Kyber { read_lat, .. } => read_lat.is_active(),
+            Self::Deadline { fifo_batch, .. } => fifo_batch.is_active(),
+            Self::Bfq { low_latency, .. } => low_latency.is_active(),
+            Self::Unmanaged => false,
+        }
+    }
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Kyber { .. } => "kyber",
+            Self::Deadline { .. } => "mq-deadline",
+            Self::Bfq { .. } => "bfq",
+            Self::Unmanaged => "unmanaged",
+        }
+    }
+    fn apply(
+        &mut self,
+        sequentiality: f32,
+        target_latency: f32,
+        screen_on: bool,
+        math_config: &storage_math::StorageMathConfig,
+    ) {
+        match self {
+            Self::Kyber {
+                read_lat,
+                write_lat,
+            } => {
+                let targets = storage_math::calculate_kyber_targets(target_latency, math_config);
+                read_lat.update(
+                    math::sanitize_to_clean_u64(targets.read_lat_nsec, 2_000_000, 250_000),
+                    false,
+                    &cached_file::CheckStrategy::Relative(0.1),
+                );
+                write_lat.update(
+                    math::sanitize_to_clean_u64(targets.write_lat_nsec, 10_000_000, 1_000_000),
+                    false,
+                    &cached_file::CheckStrategy::Relative(0.1),
+                );
+            }
+            Self::Deadline {
+                read_expire,
+                write_expire,
+                fifo_batch,
+            } => {
+                let targets = storage_math::calculate_deadline_targets(sequentiality, math_config);
+                read_expire.update(
+                    math::sanitize_to_clean_u64(targets.read_expire_ms, 500, 10),
+                    false,
+                    &cached_file::CheckStrategy::Relative(0.1),
+                );
+                write_expire.update(
+                    math::sanitize_to_clean_u64(targets.write_expire_ms, 5000, 100),
+                    false,
+                    &cached_file::CheckStrategy::Relative(0.1),
+                );
+                fifo_batch.update(
+                    math::sanitize_to_u64(targets.fifo_batch, 16),
+                    false,
+                    &cached_file::CheckStrategy::Absolute(4),
+                );
+            }
+            Self::Bfq {
+                low_latency,
+                enabled,
+            } => {
+                *enabled = storage_math::should_enable_bfq_low_latency(
+                    *enabled,
+                    sequentiality,
+                    screen_on,
+                    math_config,
+                );
+                low_latency.update(
+                    u64::from(*enabled),
+                    false,
+                    &cached_file::CheckStrategy::Strict,
+                );
+            }
+            Self::Unmanaged => {}
+        }
+    }
+}
+
+struct DeviceState {
+    device: &'static discovery::BlockDevice,
     read_ahead: cached_file::CachedFile,
     nr_requests: cached_file::CachedFile,
-    psi_monitor: psi_monitor::PsiMonitor,
     disk_monitor: disk_monitor::DiskMonitor,
     prev_io_stats: disk_monitor::IoStats,
-    workload_state: storage_math::WorkloadState,
-    storage_math_config: storage_math::StorageMathConfig,
-    storage_kernel_limits: storage_math::StorageKernelLimits,
     last_tick: time::Instant,
+    workload_state: storage_math::WorkloadState,
+    kernel_limits: storage_math::StorageKernelLimits,
     current_read_ahead: f32,
     current_nr_requests: f32,
+    last_delta: storage_math::IoDelta,
+    scheduler: SchedulerKnobs,
+    selector: Option<iosched_math::SchedulerSelector>,
+    select_config: iosched_math::IoSchedSelectConfig,
     poller: poll_math::AdaptivePoller,
     next_wake_ms: i32,
+    failures: u32,
 }
 
-impl StorageController {
-    pub fn new() -> Result<Self, types::QosError> {
-        log::info!("StorageController: Initializing...");
-        let config_limits = kernel_limits::GlobalConfig::default().storage_config;
-        let storage_math_config = storage_math::StorageMathConfig::default();
-        let storage_kernel_limits = storage_math::StorageKernelLimits {
-            min_read_ahead: config_limits.min_read_ahead as f32,
-            max_read_ahead: config_limits.max_read_ahead as f32,
-            min_nr_requests: config_limits.min_nr_requests as f32,
-            max_nr_requests: config_limits.max_nr_requests as f32,
-        };
-        let raw_fd = kernel::register_psi_trigger(sys_paths::K_PSI_IO_PATH, 250_000, 1_000_000)
-            .map_err(|e| types::QosError::FfiError(format!("Storage PSI Error: {e}")))?;
-        let fd = unsafe { os::fd::FromRawFd::from_raw_fd(raw_fd) };
-        let ra_path = sys_paths::get_read_ahead_path();
-        let nr_path = sys_paths::get_nr_requests_path();
-        let read_ahead = cached_file::CachedFile::new_opt(
-            filesystem::open_file_for_write(ra_path.to_str().unwrap_or_default()).ok(),
-            0,
-        );
-        let nr_requests = cached_file::CachedFile::new_opt(
-            filesystem::open_file_for_write(nr_path.to_str().unwrap_or_default()).ok(),
-            0,
-        );
+impl DeviceState {
+    fn open(
+        device: &'static discovery::BlockDevice,
+        base_limits: kernel_limits::StorageKernelLimitsConfig,
+    ) -> Option<Self> {
+        let limits = base_limits.for_class(device.class);
+        let read_ahead = open_knob(&device.queue_path("read_ahead_kb"), 0);
+        let nr_requests = open_knob(&device.queue_path("nr_requests"), 0);
         if !read_ahead.is_active() && !nr_requests.is_active() {
-            return Err(types::QosError::SystemCheckFailed(
-                "No storage block tunables found.".to_string(),
-            ));
+            return None;
         }
-        let psi_monitor = psi_monitor::PsiMonitor::new(sys_paths::K_PSI_IO_PATH)?;
-        let stats_path = sys_paths::get_diskstats_path();
-        let mut disk_monitor =
-            disk_monitor::DiskMonitor::new(stats_path.to_str().unwrap_or_default())?;
-        let initial_stats = disk_monitor
-            .read_stats()
-            .unwrap_or(disk_monitor::IoStats::default());
-        let poller = poll_math::AdaptivePoller::new(1.2, 0.08, poll_math::PollerConfig::default());
-        let mut controller = Self {
-            fd,
+        let mut disk_monitor = disk_monitor::DiskMonitor::new(device.stat_path().to_str()?).ok()?;
+        let prev_io_stats = disk_monitor.read_stats().unwrap_or_default();
+        Some(Self {
+            device,
             read_ahead,
             nr_requests,
-            psi_monitor,
             disk_monitor,
-            prev_io_stats: initial_stats,
-            workload_state: storage_math::WorkloadState::default(),
-            storage_math_config,
-            storage_kernel_limits,
+            prev_io_stats,
             last_tick: time::Instant::now(),
-            current_read_ahead: config_limits.min_read_ahead as f32,
-            current_nr_requests: config_limits.max_nr_requests as f32,
-            poller,
+            workload_state: storage_math::WorkloadState::default(),
+            kernel_limits: storage_math::StorageKernelLimits {
+                min_read_ahead: limits.min_read_ahead as f32,
+                max_read_ahead: limits.max_read_ahead as f32,
+                min_nr_requests: limits.min_nr_requests as f32,
+                max_nr_requests: limits.max_nr_requests as f32,
+            },
+            current_read_ahead: limits.min_read_ahead as f32,
+            current_nr_requests: limits.max_nr_requests as f32,
+            last_delta: storage_math::IoDelta::default(),
+            scheduler: SchedulerKnobs::open(device),
+            selector: if settings::get_bool("iosched_autoselect", true) {
+                device.active_scheduler().and_then(|current| {
+                    iosched_math::SchedulerSelector::new(
+                        &device.available_schedulers(),
+                        &current,
+                        time::Instant::now(),
+                    )
+                })
+            } else {
+                None
+            },
+            select_config: iosched_math::IoSchedSelectConfig::default(),
+            poller: poll_math::AdaptivePoller::new(1.2, 0.08, poll_math::PollerConfig::default()),
             next_wake_ms: loop_settings::MIN_POLLING_MS as i32,
-        };
-        controller.apply_values(true);
-        Ok(controller)
+            failures: 0,
+        })
     }
-    fn update_io_logic(
+    fn update(
         &mut self,
-        context: &mut state::DaemonContext,
-    ) -> Result<(), types::QosError> {
-        let psi_data = self.psi_monitor.read_state()?;
-        let current_io_stats = self.disk_monitor.read_stats()?;
+        psi_data: &psi_monitor::PsiData,
+        screen_on: bool,
+        math_config: &storage_math::StorageMathConfig,
+    ) -> Result<u64, types::QosError> {
         let now = time::Instant::now();
-        let dt_duration = now.duration_since(self.last_tick);
+        let current_io_stats = match self.disk_monitor.read_stats() {
+            Ok(stats) => stats,
+            Err(e) => {
+                self.last_tick = now;
+                self.last_delta = storage_math::IoDelta::default();
+                self.failures = self.failures.saturating_add(1);
+                self.next_wake_ms = (loop_settings::MIN_POLLING_MS as i32)
+                    .saturating_mul(1 << self.failures.min(10))
+                    .min(MAX_FAILURE_BACKOFF_MS);
+                return Err(e);
+            }
+        };
+        if self.failures > 0 {
+            self.failures = 0;
+            self.last_tick = now;
+            self.prev_io_stats = current_io_stats;
+            self.next_wake_ms = loop_settings::MIN_POLLING_MS as i32;
+            return Ok(0);
+        }
+        let dt_real = now
+            .duration_since(self.last_tick)
+            .as_secs_f32()
+            .max(0.000_001);
         self.last_tick = now;
-        let dt_real = dt_duration.as_secs_f32().max(0.000_001);
         let delta =
             storage_math::calculate_io_deltas(&current_io_stats, &self.prev_io_stats, dt_real);
         self.prev_io_stats = current_io_stats;
-        context.pressure.io_psi = psi_data.some.current;
-        context.pressure.io_saturation = current_io_stats.in_flight as f32;
-        if current_io_stats.in_flight == 0 && psi_data.some.current < 0.10 {
-            self.next_wake_ms = self.storage_math_config.idle_poll_interval.max(500.0) as i32;
-            return Ok(());
+        self.last_delta = delta;
+        let psi_some = psi_data.some.current;
+        let in_flight = current_io_stats.in_flight as f32;
+        if current_io_stats.in_flight == 0 && psi_some < 0.10 {
+            self.next_wake_ms = math_config.idle_poll_interval.max(500.0) as i32;
+            return Ok(0);
         }
-        let req_size_ratio =
-            storage_math::calculate_request_size_ratio(&delta, &self.storage_math_config);
+        let req_size_ratio = storage_math::calculate_request_size_ratio(&delta, math_config);
         let merge_ratio = storage_math::calculate_merge_ratio(&delta);
-        let pressure_ratio = storage_math::calculate_pressure_ratio(
-            current_io_stats.in_flight as f32,
-            &self.storage_math_config,
-        );
+        let pressure_ratio = storage_math::calculate_pressure_ratio(in_flight, math_config);
         let sequentiality = storage_math::resolve_sequentiality_factor(
             &mut self.workload_state,
             req_size_ratio,
             merge_ratio,
             pressure_ratio,
-            &self.storage_math_config,
+            math_config,
         );
         let calculated_ra =
-            storage_math::calculate_target_read_ahead(sequentiality, &self.storage_kernel_limits);
-        let lambda_eff =
-            storage_math::calculate_weighted_throughput(&delta, &self.storage_math_config);
-        let target_latency = storage_math::calculate_target_latency(
-            psi_data.some.current,
-            &self.storage_math_config,
-        );
-        let current_latency = storage_math::calculate_effective_latency(
-            &delta,
-            lambda_eff,
-            current_io_stats.in_flight as f32,
-        );
-        let calculated_nr = storage_math::calculate_next_queue_depth(
-            lambda_eff,
-            current_latency,
-            target_latency,
-            self.current_nr_requests,
-            psi_data.some.current,
-            &self.storage_math_config,
-            &self.storage_kernel_limits,
-        );
-        if storage_math::should_update_nr_requests(
-            calculated_nr,
-            self.current_nr_requests,
-            &self.storage_math_config,
-            &self.storage_kernel_limits,
-        ) {
-            self.current_nr_requests = calculated_nr;
+            storage_math::calculate_target_read_ahead(sequentiality, &self.kernel_limits);
+        let lambda_eff = storage_math::calculate_weighted_throughput(&delta, math_config);
+        let target_latency = storage_math::calculate_target_latency(psi_some, math_config);
+        if !storage_math::is_discard_heavy(&delta, math_config) {
+            if let Some(selector) = self.selector.as_mut() {
+                selector.observe(delta.service_time_ms, sequentiality);
+            }
+            let current_latency =
+                storage_math::calculate_effective_latency(&delta, lambda_eff, in_flight);
+            let calculated_nr = storage_math::calculate_next_queue_depth(
+                lambda_eff,
+                current_latency,
+                target_latency,
+                self.current_nr_requests,
+                psi_some,
+                math_config,
+                &self.kernel_limits,
+            );
+            if storage_math::should_update_nr_requests(
+                calculated_nr,
+                self.current_nr_requests,
+                math_config,
+                &self.kernel_limits,
+            ) {
+                self.current_nr_requests = calculated_nr;
+            }
         }
         self.current_read_ahead = calculated_ra;
-        if storage_math::is_congestion_critical(
-            psi_data.some.current,
-            current_io_stats.in_flight as f32,
-            &self.storage_math_config,
-        ) {
+        self.reselect_scheduler(now);
+        self.scheduler
+            .apply(sequentiality, target_latency, screen_on, math_config);
+        if storage_math::is_congestion_critical(psi_some, in_flight, math_config) {
             self.next_wake_ms = loop_settings::MIN_POLLING_MS as i32;
         } else {
             self.next_wake_ms = self.poller.calculate_next_interval(
-                psi_data.some.current,
+                psi_some,
                 psi_data.some.avg300,
                 psi_data.some.velocity,
             ) as i32;
         }
         self.apply_values(false);
-        Ok(())
+        Ok(current_io_stats.in_flight)
     }
-    fn apply_values(&mut self, force: bool) {
-        let ra_u64 = math::sanitize_to_clean_u64(
+    fn reselect_scheduler(&mut self, now: time::Instant) {
+        let Some(selector) = self.selector.as_mut() else {
+            return;
+        };
+        let (target, reason) = match selector.evaluate(now, &self.select_config) {
+            iosched_math::SelectorDecision::Stay => return,
+            iosched_math::SelectorDecision::Switch(name) => (name, "trial"),
+            iosched_math::SelectorDecision::Revert(name) => (name, "revert"),
+        };
+        let Some(path) = self
+            .device
+            .queue_path("scheduler")
+            .to_str()
+            .map(str::to_string)
+        else {
+            return;
+        };
+        match filesystem::write_to_file(&path, target) {
+            Ok(()) => {
+                log::info!(
+                    "Storage: {} scheduler {} -> {target} ({reason}, p90={:.2} ms)",
+                    self.device.name,
+                    selector.current(),
+                    selector.last_p90()
+                );
+                selector.switched(target, now);
+                self.scheduler = SchedulerKnobs::open(self.device);
+                // The kernel resets nr_requests when the elevator changes.
+                self.apply_values(true);
+            }
+            Err(e) => {
+                log::debug!("Storage: {} cannot select {target}: {e}", self.device.name);
+                selector.switch_failed(target);
+            }
+        }
+    }
+    fn is_due(&self, now: time::Instant) -> bool {
+        now.duration_since(self.last_tick).as_millis() >= self.next_wake_ms as u128
+    }
+    fn remaining_ms(&self, now: time::Instant) -> i32 {
+        let elapsed = now.duration_since(self.last_tick).as_millis();
+        (self.next_wake_ms as u128).saturating_sub(elapsed) as i32
+    }
+    fn read_ahead_kb(&self) -> u64 {
+        math::sanitize_to_clean_u64(
             self.current_read_ahead,
-            self.storage_kernel_limits.max_read_ahead as u64,
+            self.kernel_limits.max_read_ahead as u64,
             32,
-        );
+        )
+    }
+    fn apply_values(&mut self, force: bool) {
+        let ra_u64 = self.read_ahead_kb();
         let nr_u64 = math::sanitize_to_clean_u64(
             self.current_nr_requests,
-            self.storage_kernel_limits.min_nr_requests as u64,
+            self.kernel_limits.min_nr_requests as u64,
             16,
         );
         self.read_ahead
@@ -181,6 +511,221 @@ impl StorageController {
     }
 }
 
+struct DmState {
+    read_ahead: cached_file::CachedFile,
+    backing: Vec<usize>,
+}
+
+pub struct StorageController {
+    fd: fs::File,
+    psi_monitor: psi_monitor::PsiMonitor,
+    devices: Vec<DeviceState>,
+    dm_devices: Vec<DmState>,
+    writeback: Option<WritebackControl>,
+    screen: screen::ScreenSensor,
+    screen_on: bool,
+    last_screen_check: time::Instant,
+    storage_math_config: storage_math::StorageMathConfig,
+    last_status: time::Instant,
+}
+
+impl StorageController {
+    pub fn new() -> Result<Self, types::QosError> {
+        log::info!("StorageController: Initializing...");
+        let config_limits = kernel_limits::GlobalConfig::default().storage_config;
+        let storage_math_config = storage_math::StorageMathConfig::default();
+        let raw_fd = kernel::register_psi_trigger(sys_paths::K_PSI_IO_PATH, 250_000, 1_000_000)
+            .map_err(|e| types::QosError::FfiError(format!("Storage PSI Error: {e}")))?;
+        let fd = unsafe { os::fd::FromRawFd::from_raw_fd(raw_fd) };
+        let devices: Vec<DeviceState> = sys_paths::get_block_devices()
+            .iter()
+            .filter_map(|device| DeviceState::open(device, config_limits))
+            .collect();
+        if devices.is_empty() {
+            return Err(types::QosError::SystemCheckFailed(
+                "No storage block tunables found.".to_string(),
+            ));
+        }
+        for state in &devices {
+            log::info!(
+                "StorageController: Managing {} ({}, {})",
+                state.device.name,
+                state.device.class.as_str(),
+                state.scheduler.name()
+            );
+        }
+        let dm_devices = sys_paths::get_dm_devices()
+            .iter()
+            .filter_map(|dm| {
+                let backing: Vec<usize> = dm
+                    .backing
+                    .iter()
+                    .filter_map(|name| devices.iter().position(|d| &d.device.name == name))
+                    .collect();
+                if backing.is_empty() {
+                    return None;
+                }
+                let read_ahead = open_knob(
+                    &path::Path::new(sys_paths::K_SYS_BLOCK_DIR)
+                        .join(&dm.name)
+                        .join("queue/read_ahead_kb"),
+                    0,
+                );
+                read_ahead.is_active().then_some(DmState {
+                    read_ahead,
+                    backing,
+                })
+            })
+            .collect();
+        let psi_monitor = psi_monitor::PsiMonitor::new(sys_paths::K_PSI_IO_PATH)?;
+        let mut controller = Self {
+            fd,
+            psi_monitor,
+            devices,
+            dm_devices,
+            writeback: WritebackControl::open(&config_limits),
+            screen: screen::ScreenSensor::new(),
+            screen_on: true,
+            last_screen_check: time::Instant::now(),
+            storage_math_config,
+            last_status: time::Instant::now(),
+        };
+        for device in &mut controller.devices {
+            device.apply_values(true);
+        }
+        controller.sync_dm_devices(true);
+        controller.screen_on = controller.screen.is_on().unwrap_or(true);
+        if let Some(writeback) = controller.writeback.as_mut() {
+            writeback.update(
+                0.0,
+                0.0,
+                controller.screen_on,
+                &controller.storage_math_config,
+                true,
+            );
+        }
+        Ok(controller)
+    }
+    fn update_io_logic(
+        &mut self,
+        context: &mut state::DaemonContext,
+        update_all: bool,
+    ) -> Result<(), types::QosError> {
+        let psi_data = self.psi_monitor.read_state()?;
+        context.pressure.io_psi = psi_data.some.current;
+        let now = time::Instant::now();
+        if now.duration_since(self.last_screen_check) >= SCREEN_INTERVAL {
+            self.last_screen_check = now;
+            self.screen_on = self.screen.is_on().unwrap_or(true);
+        }
+        let mut in_flight_total = 0;
+        let mut sampled = false;
+        for device in &mut self.devices {
+            if !update_all && !device.is_due(now) {
+                in_flight_total += device.prev_io_stats.in_flight;
+                continue;
+            }
+            match device.update(&psi_data, self.screen_on, &self.storage_math_config) {
+                Ok(in_flight) => {
+                    in_flight_total += in_flight;
+                    sampled = true;
+                }
+                Err(e) => log::debug!("Storage: {} update failed: {e}", device.device.name),
+            }
+        }
+        context.pressure.io_saturation = in_flight_total as f32;
+        self.sync_dm_devices(false);
+        // Devices not due this pass contribute their last measured write rate.
+        if let Some(writeback) = self.writeback.as_mut()
+            && sampled
+        {
+            let write_kbps = self.devices.iter().map(|d| d.last_delta.write_kbps).sum();
+            writeback.update(
+                psi_data.some.current,
+                write_kbps,
+                self.screen_on,
+                &self.storage_math_config,
+                false,
+            );
+        }
+        if now.duration_since(self.last_status) >= STATUS_INTERVAL {
+            self.last_status = now;
+            self.publish_status();
+        }
+        Ok(())
+    }
+    fn sync_dm_devices(&mut self, force: bool) {
+        for dm in &mut self.dm_devices {
+            let read_ahead = dm
+                .backing
+                .iter()
+                .map(|&i| self.devices[i].read_ahead_kb())
+                .max()
+                .unwrap_or(0);
+            if read_ahead > 0 {
+                dm.read_ahead
+                    .update(read_ahead, force, &cached_file::CheckStrategy::Absolute(32));
+            }
+        }
+    }
+    fn publish_status(&self) {
+        let mut body = String::with_capacity(160 * self.devices.len());
+        for device in &self.devices {
+            let delta = &device.last_delta;
+            let _ = writeln!(
+                body,
+                "{}={} sched={} autoselect={} read_ahead_kb={} nr_requests={:.0} poll_ms={}",
+                device.device.name,
+                device.device.class.as_str(),
+                device.scheduler.name(),
+                device.selector.as_ref().map_or("off", |s| if s.is_trial() {
+                    "trial"
+                } else {
+                    "stable"
+                }),
+                device.read_ahead_kb(),
+                device.current_nr_requests,
+                device.next_wake_ms
+            );
+            let _ = write!(
+                body,
+                "{} svc_ms r={:.2} w={:.2}",
+                device.device.name, delta.read_service_ms, delta.write_service_ms
+            );
+            if delta.has_flush {
+                let _ = write!(
+                    body,
+                    " flush={:.2} flush_iops={:.1}",
+                    delta.flush_service_ms, delta.flush_iops
+                );
+            } else {
+                body.push_str(" flush=n/a");
+            }
+            if delta.has_discard {
+                let _ = writeln!(
+                    body,
+                    " discard_iops={:.1} discard_kbps={:.0} discard_busy={:.2}",
+                    delta.discard_iops, delta.discard_kbps, delta.discard_busy_ratio
+                );
+            } else {
+                body.push_str(" discard=n/a\n");
+            }
+        }
+        if let Some(writeback) = &self.writeback {
+            let targets = &writeback.targets;
+            let _ = writeln!(
+                body,
+                "writeback dirty_ratio={:.0} background={:.0} expire_cs={:.0} writeback_cs={:.0}",
+                targets.dirty_ratio,
+                targets.dirty_background_ratio,
+                targets.dirty_expire_cs,
+                targets.dirty_writeback_cs
+            );
+        }
+        status::publish("storage", body);
+    }
+}
+
 impl traits::EventHandler for StorageController {
     fn as_raw_fd(&self) -> os::fd::RawFd {
         os::fd::AsRawFd::as_raw_fd(&self.fd)
@@ -191,7 +736,7 @@ impl traits::EventHandler for StorageController {
     ) -> Result<traits::LoopAction, types::QosError> {
         let mut buf = [0u8; 8];
         let _ = io::Read::read(&mut self.fd, &mut buf);
-        if let Err(e) = self.update_io_logic(context) {
+        if let Err(e) = self.update_io_logic(context, true) {
             log::warn!("Storage Error: {e}");
         }
         Ok(traits::LoopAction::Continue)
@@ -200,13 +745,19 @@ impl traits::EventHandler for StorageController {
         &mut self,
         context: &mut state::DaemonContext,
     ) -> Result<traits::LoopAction, types::QosError> {
-        if let Err(e) = self.update_io_logic(context) {
+        if let Err(e) = self.update_io_logic(context, false) {
             log::warn!("Storage Timeout Error: {e}");
         }
         Ok(traits::LoopAction::Continue)
     }
     fn get_timeout_ms(&self) -> i32 {
-        self.next_wake_ms
+        let now = time::Instant::now();
+        self.devices
+            .iter()
+            .map(|d| d.remaining_ms(now))
+            .min()
+            .unwrap_or(loop_settings::MIN_POLLING_MS as i32)
+            .max(10)
     }
     fn get_poll_flags(&self) -> rustix::event::epoll::EventFlags {
         rustix::event::epoll::EventFlags::PRI | rustix::event::epoll::EventFlags::ERR
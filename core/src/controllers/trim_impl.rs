@@ -0,0 +1,396 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::config::trim_state;
+use crate::daemon::{state, status, traits, types};
+use crate::hal::kernel;
+use crate::monitors::{idle_monitor, psi_monitor};
+use crate::resources::sys_paths;
+
+use std::fmt::Write;
+use std::{fs, io, os, sync, thread, time};
+
+const TRIM_MOUNTS: &[&str] = &["/data", "/cache"];
+const SHUTDOWN_WAIT: time::Duration = time::Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy)]
+struct TrimConfig {
+    idle_interval_ms: i32,
+    active_interval_ms: i32,
+    temp_ceiling: f32,
+    io_psi_ceiling: f32,
+    chunk_bytes: u64,
+    min_trim_interval: time::Duration,
+    preempt_backoff: time::Duration,
+}
+
+impl Default for TrimConfig {
+    fn default() -> Self {
+        Self {
+            idle_interval_ms: 60_000,
+            active_interval_ms: 1000,
+            temp_ceiling: 36.0,
+            io_psi_ceiling: 10.0,
+            chunk_bytes: 1 << 30,
+            min_trim_interval: time::Duration::from_hours(24),
+            preempt_backoff: time::Duration::from_mins(10),
+        }
+    }
+}
+
+#[derive(Default)]
+struct TrimProgress {
+    running: sync::atomic::AtomicBool,
+    stop: sync::atomic::AtomicBool,
+    failed: sync::atomic::AtomicBool,
+    cursor: sync::atomic::AtomicU64,
+    trimmed: sync::atomic::AtomicU64,
+}
+
+struct TrimJob {
+    dir: fs::File,
+    start: u64,
+    end: u64,
+    chunk: u64,
+}
+
+fn run_job(job: &TrimJob, progress: &TrimProgress) {
+    let mut offset = job.start;
+    while offset < job.end && !progress.stop.load(sync::atomic::Ordering::Acquire) {
+        // statvfs leaves out filesystem overhead, so the last range is open
+        // ended and the kernel clamps it to the real end of the device.
+        let len = if job.end - offset <= job.chunk {
+            u64::MAX
+        } else {
+            job.chunk
+        };
+        let mut range = kernel::FstrimRange {
+            start: offset,
+            len,
+            minlen: 0,
+        };
+        if let Err(e) = kernel::fitrim(&job.dir, &mut range) {
+            log::warn!("TrimController: FITRIM at offset {offset} failed: {e}");
+            progress.failed.store(true, sync::atomic::Ordering::Release);
+            break;
+        }
+        progress
+            .trimmed
+            .fetch_add(range.len, sync::atomic::Ordering::AcqRel);
+        offset = offset.saturating_add(job.chunk).min(job.end);
+        progress
+            .cursor
+            .store(offset, sync::atomic::Ordering::Release);
+    }
+}
+
+fn unix_now() -> u64 {
+    time::SystemTime::now()
+        .duration_since(time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn is_mounted(mounts: &str, target: &str) -> bool {
+    mounts
+        .lines()
+        .any(|line| line.split_ascii_whitespace().nth(1) == Some(target))
+}
+
+struct TrimTarget {
+    mount: &'static str,
+    size: u64,
+    record: trim_state::TrimRecord,
+}
+
+impl TrimTarget {
+    fn is_due(&self, now: u64, min_interval: time::Duration) -> bool {
+        self.record.cursor > 0
+            || now.saturating_sub(self.record.last_trim) >= min_interval.as_secs()
+    }
+}
+
+pub struct TrimController {
+    psi_fd: fs::File,
+    io_psi: psi_monitor::PsiMonitor,
+    idle: idle_monitor::IdleMonitor,
+    targets: Vec<TrimTarget>,
+    tunables: TrimConfig,
+    tx: sync::mpsc::Sender<TrimJob>,
+    progress: sync::Arc<TrimProgress>,
+    active: Option<usize>,
+    backoff_until: Option<time::Instant>,
+    blocker: &'static str,
+}
+
+impl TrimController {
+    pub fn new() -> Result<Self, types::QosError> {
+        log::info!("TrimController: Initializing...");
+        let mounts = fs::read_to_string(sys_paths::K_PROC_MOUNTS).unwrap_or_default();
+        let stored = trim_state::load();
+        let targets: Vec<TrimTarget> = TRIM_MOUNTS
+            .iter()
+            .filter(|&&mount| is_mounted(&mounts, mount))
+            .filter_map(|&mount| {
+                let stats = rustix::fs::statvfs(mount).ok()?;
+                let size = stats.f_blocks.saturating_mul(stats.f_frsize);
+                let mut record = stored
+                    .iter()
+                    .find(|r| r.mount == mount)
+                    .cloned()
+                    .unwrap_or_else(|| trim_state::TrimRecord {
+                        mount: mount.to_string(),
+                        ..trim_state::TrimRecord::default()
+                    });
+                if record.cursor >= size {
+                    record.cursor = 0;
+                }
+                (size > 0).then_some(TrimTarget {
+                    mount,
+                    size,
+                    record,
+                })
+            })
+            .collect();
+        if targets.is_empty() {
+            return Err(types::QosError::SystemCheckFailed(
+                "No trimmable mounts found.".to_string(),
+            ));
+        }
+        let raw_fd = kernel::register_psi_trigger(sys_paths::K_PSI_IO_PATH, 150_000, 1_000_000)
+            .map_err(|e| types::QosError::FfiError(format!("Trim PSI Error: {e}")))?;
+        let psi_fd = unsafe { os::fd::FromRawFd::from_raw_fd(raw_fd) };
+        let (tx, rx) = sync::mpsc::channel::<TrimJob>();
+        let progress = sync::Arc::new(TrimProgress::default());
+        let worker_progress = sync::Arc::clone(&progress);
+        thread::Builder::new()
+            .name("TrimWorker".into())
+            .stack_size(64 * 1024)
+            .spawn(move || {
+                while let Ok(job) = rx.recv() {
+                    run_job(&job, &worker_progress);
+                    worker_progress
+                        .running
+                        .store(false, sync::atomic::Ordering::Release);
+                }
+            })
+            .map_err(|e| {
+                types::QosError::SystemCheckFailed(format!("Failed to spawn trim thread: {e}"))
+            })?;
+        Ok(Self {
+            psi_fd,
+            io_psi: psi_monitor::PsiMonitor::new(sys_paths::K_PSI_IO_PATH)?,
+            idle: idle_monitor::IdleMonitor::new(),
+            targets,
+            tunables: TrimConfig::default(),
+            tx,
+            progress,
+            active: None,
+            backoff_until: None,
+            blocker: "startup",
+        })
+    }
+    fn is_running(&self) -> bool {
+        self.progress.running.load(sync::atomic::Ordering::Acquire)
+    }
+    fn request_stop(&self) {
+        self.progress
+            .stop
+            .store(true, sync::atomic::Ordering::Release);
+    }
+    fn current_blocker(&mut self, now: time::Instant) -> Option<&'static str> {
+        let conditions = self.idle.sample();
+        if let Some(reason) = conditions.blocker(self.tunables.temp_ceiling) {
+            return Some(reason);
+        }
+        if self.backoff_until.is_some_and(|until| now < until) {
+            return Some("backoff");
+        }
+        let io_psi = self.io_psi.read_state().map_or(0.0, |d| d.some.current);
+        (io_psi > self.tunables.io_psi_ceiling).then_some("io_pressure")
+    }
+    fn collect_finished(&mut self) {
+        let Some(index) = self.active.take() else {
+            return;
+        };
+        let target = &mut self.targets[index];
+        let cursor = self.progress.cursor.load(sync::atomic::Ordering::Acquire);
+        let trimmed = self.progress.trimmed.load(sync::atomic::Ordering::Acquire);
+        let record = &mut target.record;
+        record.pending_bytes = record.pending_bytes.saturating_add(trimmed);
+        record.total_bytes = record.total_bytes.saturating_add(trimmed);
+        if self.progress.failed.load(sync::atomic::Ordering::Acquire) {
+            record.cursor = 0;
+            record.pending_bytes = 0;
+            record.last_trim = unix_now();
+        } else if cursor >= target.size {
+            record.cursor = 0;
+            record.last_trim = unix_now();
+            record.last_bytes = record.pending_bytes;
+            record.pending_bytes = 0;
+            log::info!(
+                "TrimController: {} trimmed {} MiB",
+                target.mount,
+                record.last_bytes >> 20
+            );
+        } else {
+            record.cursor = cursor;
+            log::info!(
+                "TrimController: {} paused at {} MiB of {} MiB",
+                target.mount,
+                cursor >> 20,
+                target.size >> 20
+            );
+        }
+        let records: Vec<trim_state::TrimRecord> =
+            self.targets.iter().map(|t| t.record.clone()).collect();
+        if let Err(e) = trim_state::save(&records) {
+            log::warn!("TrimController: Failed to save trim state: {e}");
+        }
+    }
+    fn dispatch(&mut self, index: usize) {
+        let target = &self.targets[index];
+        let dir = match fs::File::open(target.mount) {
+            Ok(dir) => dir,
+            Err(e) => {
+                log::debug!("TrimController: Cannot open {}: {e}", target.mount);
+                return;
+            }
+        };
+        self.progress
+            .stop
+            .store(false, sync::atomic::Ordering::Release);
+        self.progress
+            .failed
+            .store(false, sync::atomic::Ordering::Release);
+        self.progress
+            .trimmed
+            .store(0, sync::atomic::Ordering::Release);
+        self.progress
+            .cursor
+            .store(target.record.cursor, sync::atomic::Ordering::Release);
+        self.progress
+            .running
+            .store(true, sync::atomic::Ordering::Release);
+        let job = TrimJob {
+            dir,
+            start: target.record.cursor,
+            end: target.size,
+            chunk: self.tunables.chunk_bytes,
+        };
+        if self.tx.send(job).is_ok() {
+            log::info!(
+                "TrimController: Trimming {} from {} MiB",
+                target.mount,
+                target.record.cursor >> 20
+            );
+            self.active = Some(index);
+        } else {
+            self.progress
+                .running
+                .store(false, sync::atomic::Ordering::Release);
+        }
+    }
+    fn evaluate(&mut self) {
+        let now = time::Instant::now();
+        let blocker = self.current_blocker(now);
+        self.blocker = blocker.unwrap_or("none");
+        if self.is_running() {
+            if blocker.is_some() {
+                self.request_stop();
+            }
+        } else {
+            self.collect_finished();
+            if blocker.is_none() {
+                let unix = unix_now();
+                if let Some(index) = self
+                    .targets
+                    .iter()
+                    .position(|t| t.is_due(unix, self.tunables.min_trim_interval))
+                {
+                    self.dispatch(index);
+                }
+            }
+        }
+        self.publish();
+    }
+    fn publish(&self) {
+        let unix = unix_now();
+        let mut body = String::with_capacity(128 * (self.targets.len() + 1));
+        let running = self.active.and_then(|i| self.targets.get(i));
+        let _ = writeln!(
+            body,
+            "state={} blocker={}",
+            running.map_or("idle", |t| t.mount),
+            self.blocker
+        );
+        for target in &self.targets {
+            let record = &target.record;
+            let age = if record.last_trim == 0 {
+                "never".to_string()
+            } else {
+                format!("{}h", unix.saturating_sub(record.last_trim) / 3600)
+            };
+            let _ = writeln!(
+                body,
+                "{} size_mib={} cursor_mib={} last_trim={age} last_mib={} total_mib={}",
+                target.mount,
+                target.size >> 20,
+                record.cursor >> 20,
+                record.last_bytes >> 20,
+                record.total_bytes >> 20
+            );
+        }
+        status::publish("trim", body);
+    }
+}
+
+impl Drop for TrimController {
+    fn drop(&mut self) {
+        if self.active.is_none() {
+            return;
+        }
+        self.request_stop();
+        // The cursor only advances after a finished chunk, so it is safe to
+        // persist even if the worker is still inside FITRIM when we give up.
+        let deadline = time::Instant::now() + SHUTDOWN_WAIT;
+        while self.is_running() && time::Instant::now() < deadline {
+            thread::sleep(time::Duration::from_millis(50));
+        }
+        self.collect_finished();
+    }
+}
+
+impl traits::EventHandler for TrimController {
+    fn as_raw_fd(&self) -> os::fd::RawFd {
+        os::fd::AsRawFd::as_raw_fd(&self.psi_fd)
+    }
+    fn on_event(
+        &mut self,
+        _context: &mut state::DaemonContext,
+    ) -> Result<traits::LoopAction, types::QosError> {
+        let mut buf = [0u8; 8];
+        let _ = io::Read::read(&mut self.psi_fd, &mut buf);
+        if self.is_running() {
+            self.request_stop();
+            self.backoff_until = Some(time::Instant::now() + self.tunables.preempt_backoff);
+            self.blocker = "io_pressure";
+        }
+        Ok(traits::LoopAction::Continue)
+    }
+    fn on_timeout(
+        &mut self,
+        _context: &mut state::DaemonContext,
+    ) -> Result<traits::LoopAction, types::QosError> {
+        self.evaluate();
+        Ok(traits::LoopAction::Continue)
+    }
+    fn get_timeout_ms(&self) -> i32 {
+        if self.active.is_some() {
+            self.tunables.active_interval_ms
+        } else {
+            self.tunables.idle_interval_ms
+        }
+    }
+    fn get_poll_flags(&self) -> rustix::event::epoll::EventFlags {
+        rustix::event::epoll::EventFlags::PRI | rustix::event::epoll::EventFlags::ERR
+    }
+}
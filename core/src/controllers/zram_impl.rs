@@ -0,0 +1,351 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::algorithms::zram_math;
+use crate::config::settings;
+use crate::daemon::{state, status, traits, types};
+use crate::hal::filesystem;
+use crate::monitors::{psi_monitor, zram_monitor};
+use crate::resources::{discovery, sys_paths};
+use crate::utils::{cached_file, math, tier};
+
+use std::fmt::Write;
+use std::{fs, os, sync, thread, time};
+
+const INTERVAL_MS: i32 = 5000;
+const STANDARD_ALGORITHMS: &[&str] = &["lz4", "lzo-rle", "lzo", "zstd"];
+const COMPACT_ALGORITHMS: &[&str] = &["zstd", "lz4", "lzo-rle", "lzo"];
+const RECOMPRESS_ALGORITHMS: &[&str] = &["zstd", "lz4hc", "deflate"];
+
+struct MaintenanceJob {
+    device: &'static discovery::ZramDevice,
+    action: zram_math::ZramAction,
+}
+
+fn attr_str(device: &discovery::ZramDevice, attr: &str) -> Option<String> {
+    device.attr_path(attr).to_str().map(str::to_string)
+}
+
+fn write_attr(device: &discovery::ZramDevice, attr: &str, value: &str) -> bool {
+    let Some(path) = attr_str(device, attr) else {
+        return false;
+    };
+    match filesystem::write_to_file(&path, value) {
+        Ok(()) => true,
+        Err(e) => {
+            log::debug!("ZramController: {path} <- '{value}' failed: {e}");
+            false
+        }
+    }
+}
+
+fn run_maintenance(job: &MaintenanceJob) {
+    let started = time::Instant::now();
+    let done = match job.action {
+        zram_math::ZramAction::Writeback => write_attr(job.device, "writeback", "idle"),
+        zram_math::ZramAction::Recompress => write_attr(job.device, "recompress", "type=idle"),
+        zram_math::ZramAction::Idle => true,
+    };
+    write_attr(job.device, "idle", "all");
+    log::info!(
+        "ZramController: {} {} on {} in {} ms",
+        job.action.as_str(),
+        if done { "completed" } else { "failed" },
+        job.device.name,
+        started.elapsed().as_millis()
+    );
+}
+
+fn available_algorithms(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .split_ascii_whitespace()
+        .map(|a| a.trim_start_matches('[').trim_end_matches(']'))
+}
+
+fn active_algorithm(content: &str) -> Option<&str> {
+    content
+        .split_ascii_whitespace()
+        .find_map(|a| a.strip_prefix('[')?.strip_suffix(']'))
+}
+
+fn pick_algorithm<'a>(content: &str, priorities: &[&'a str]) -> Option<&'a str> {
+    priorities
+        .iter()
+        .find(|&&candidate| available_algorithms(content).any(|a| a == candidate))
+        .copied()
+}
+
+struct ZramState {
+    device: &'static discovery::ZramDevice,
+    monitor: zram_monitor::ZramMonitor,
+    stats: zram_monitor::ZramStats,
+    algorithm: String,
+    recompress_algorithm: Option<&'static str>,
+    caps: zram_math::ZramCapabilities,
+    last_idle_mark: time::Instant,
+    last_action: zram_math::ZramAction,
+}
+
+impl ZramState {
+    fn open(device: &'static discovery::ZramDevice) -> Option<Self> {
+        let mut monitor = zram_monitor::ZramMonitor::new(device).ok()?;
+        let stats = monitor.read_stats().unwrap_or_default();
+        let algorithm = Self::select_algorithm(device, stats.disksize == 0);
+        let recompress_algorithm = Self::select_recompress_algorithm(device);
+        let has_idle = device.supports("idle");
+        let has_backing = fs::read_to_string(device.attr_path("backing_dev"))
+            .is_ok_and(|v| !v.trim().is_empty() && v.trim() != "none");
+        let caps = zram_math::ZramCapabilities {
+            recompress: has_idle && recompress_algorithm.is_some(),
+            writeback: has_idle && has_backing && device.supports("writeback"),
+        };
+        if has_idle {
+            write_attr(device, "idle", "all");
+        }
+        Some(Self {
+            device,
+            monitor,
+            stats,
+            algorithm,
+            recompress_algorithm,
+            caps,
+            last_idle_mark: time::Instant::now(),
+            last_action: zram_math::ZramAction::Idle,
+        })
+    }
+    fn select_algorithm(device: &discovery::ZramDevice, unconfigured: bool) -> String {
+        let content = fs::read_to_string(device.attr_path("comp_algorithm")).unwrap_or_default();
+        let current = active_algorithm(&content).unwrap_or("unknown").to_string();
+        let priorities = if tier::DeviceTier::get() == tier::DeviceTier::LowEnd {
+            COMPACT_ALGORITHMS
+        } else {
+            STANDARD_ALGORITHMS
+        };
+        let preferred = settings::get("zram_comp_algorithm")
+            .filter(|a| available_algorithms(&content).any(|c| c == *a))
+            .or_else(|| pick_algorithm(&content, priorities));
+        let Some(preferred) = preferred else {
+            return current;
+        };
+        if preferred == current {
+            return current;
+        }
+        if !unconfigured {
+            log::info!(
+                "ZramController: {} already initialised with {current}; {preferred} applies after reset",
+                device.name
+            );
+            return current;
+        }
+        if write_attr(device, "comp_algorithm", preferred) {
+            log::info!("ZramController: {} using {preferred}", device.name);
+            preferred.to_string()
+        } else {
+            current
+        }
+    }
+    fn select_recompress_algorithm(device: &discovery::ZramDevice) -> Option<&'static str> {
+        if !device.supports("recompress") || !device.supports("recomp_algorithm") {
+            return None;
+        }
+        let content = fs::read_to_string(device.attr_path("comp_algorithm")).unwrap_or_default();
+        let primary = active_algorithm(&content).unwrap_or_default().to_string();
+        let candidate = RECOMPRESS_ALGORITHMS
+            .iter()
+            .find(|&&a| a != primary && available_algorithms(&content).any(|c| c == a))?;
+        write_attr(
+            device,
+            "recomp_algorithm",
+            &format!("algo={candidate} priority=1"),
+        )
+        .then_some(*candidate)
+    }
+    fn idle_age_sec(&self, now: time::Instant) -> f32 {
+        now.duration_since(self.last_idle_mark).as_secs_f32()
+    }
+}
+
+pub struct ZramController {
+    dummy_fd: rustix::fd::OwnedFd,
+    devices: Vec<ZramState>,
+    mem_psi: Option<psi_monitor::PsiMonitor>,
+    swappiness: cached_file::CachedFile,
+    original_swappiness: Option<u64>,
+    target_swappiness: f32,
+    tunables: zram_math::ZramMathConfig,
+    tx: sync::mpsc::Sender<MaintenanceJob>,
+    busy: sync::Arc<sync::atomic::AtomicBool>,
+}
+
+impl ZramController {
+    pub fn new() -> Result<Self, types::QosError> {
+        log::info!("ZramController: Initializing...");
+        let devices: Vec<ZramState> = sys_paths::get_zram_devices()
+            .iter()
+            .filter_map(ZramState::open)
+            .collect();
+        if devices.is_empty() {
+            return Err(types::QosError::SystemCheckFailed(
+                "No zram devices found.".to_string(),
+            ));
+        }
+        for state in &devices {
+            log::info!(
+                "ZramController: Managing {} ({}, recompress={}, writeback={})",
+                state.device.name,
+                state.algorithm,
+                state.recompress_algorithm.unwrap_or("none"),
+                state.caps.writeback
+            );
+        }
+        let dummy_fd = rustix::event::eventfd(
+            0,
+            rustix::event::EventfdFlags::CLOEXEC | rustix::event::EventfdFlags::NONBLOCK,
+        )
+        .map_err(|e| types::QosError::SystemCheckFailed(format!("Eventfd fail: {e}")))?;
+        let (tx, rx) = sync::mpsc::channel::<MaintenanceJob>();
+        let busy = sync::Arc::new(sync::atomic::AtomicBool::new(false));
+        let worker_busy = sync::Arc::clone(&busy);
+        thread::Builder::new()
+            .name("ZramWorker".into())
+            .stack_size(64 * 1024)
+            .spawn(move || {
+                while let Ok(job) = rx.recv() {
+                    run_maintenance(&job);
+                    worker_busy.store(false, sync::atomic::Ordering::Release);
+                }
+            })
+            .map_err(|e| {
+                types::QosError::SystemCheckFailed(format!("Failed to spawn zram thread: {e}"))
+            })?;
+        let original_swappiness = fs::read_to_string(sys_paths::K_VM_SWAPPINESS)
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok());
+        Ok(Self {
+            dummy_fd,
+            devices,
+            mem_psi: psi_monitor::PsiMonitor::new(sys_paths::K_PSI_MEM_PATH).ok(),
+            swappiness: cached_file::CachedFile::new_opt(
+                filesystem::open_file_for_write(sys_paths::K_VM_SWAPPINESS).ok(),
+                original_swappiness.unwrap_or(0),
+            ),
+            original_swappiness,
+            target_swappiness: original_swappiness.unwrap_or(0) as f32,
+            tunables: zram_math::ZramMathConfig::default(),
+            tx,
+            busy,
+        })
+    }
+    fn update(&mut self) {
+        let mem_psi = self
+            .mem_psi
+            .as_mut()
+            .and_then(|m| m.read_state().ok())
+            .map_or(0.0, |d| d.some.current);
+        let now = time::Instant::now();
+        let mut fill = 0.0f32;
+        let worker_busy = self.busy.load(sync::atomic::Ordering::Acquire);
+        let mut dispatched = false;
+        for state in &mut self.devices {
+            match state.monitor.read_stats() {
+                Ok(stats) => state.stats = stats,
+                Err(e) => {
+                    log::debug!("ZramController: {} stats failed: {e}", state.device.name);
+                    continue;
+                }
+            }
+            let device_fill = state.stats.fill();
+            fill = fill.max(device_fill);
+            if worker_busy || dispatched {
+                continue;
+            }
+            let action = zram_math::decide_maintenance(
+                device_fill,
+                mem_psi,
+                state.idle_age_sec(now),
+                state.caps,
+                &self.tunables,
+            );
+            if action == zram_math::ZramAction::Idle {
+                continue;
+            }
+            self.busy.store(true, sync::atomic::Ordering::Release);
+            let job = MaintenanceJob {
+                device: state.device,
+                action,
+            };
+            if self.tx.send(job).is_ok() {
+                state.last_idle_mark = now;
+                state.last_action = action;
+                dispatched = true;
+            } else {
+                self.busy.store(false, sync::atomic::Ordering::Release);
+            }
+        }
+        self.target_swappiness = zram_math::calculate_swappiness(mem_psi, fill, &self.tunables);
+        let value =
+            math::sanitize_to_u64(self.target_swappiness, self.tunables.min_swappiness as u64);
+        self.swappiness
+            .update(value, false, &cached_file::CheckStrategy::Absolute(5));
+        self.publish(mem_psi);
+    }
+    fn publish(&self, mem_psi: f32) {
+        let mut body = String::with_capacity(160 * self.devices.len());
+        let _ = writeln!(body, "swappiness={:.0}", self.target_swappiness);
+        let _ = writeln!(body, "mem_psi={mem_psi:.2}");
+        for state in &self.devices {
+            let stats = &state.stats;
+            let _ = writeln!(
+                body,
+                "{} algo={} ratio={:.2} pages={} huge={} same={} fill={:.2} failed_rw={}/{} last={}",
+                state.device.name,
+                state.algorithm,
+                stats.compression_ratio(),
+                stats.pages_stored(),
+                stats.huge_pages,
+                stats.same_pages,
+                stats.fill(),
+                stats.failed_reads,
+                stats.failed_writes,
+                state.last_action.as_str()
+            );
+        }
+        status::publish("zram", body);
+    }
+}
+
+impl Drop for ZramController {
+    fn drop(&mut self) {
+        if let Some(original) = self.original_swappiness {
+            log::info!("ZramController: Restoring swappiness {original}.");
+            self.swappiness
+                .update(original, true, &cached_file::CheckStrategy::Strict);
+        }
+    }
+}
+
+impl traits::EventHandler for ZramController {
+    fn as_raw_fd(&self) -> os::fd::RawFd {
+        os::fd::AsRawFd::as_raw_fd(&self.dummy_fd)
+    }
+    fn on_event(
+        &mut self,
+        _context: &mut state::DaemonContext,
+    ) -> Result<traits::LoopAction, types::QosError> {
+        let mut buf = [0u8; 8];
+        let _ = rustix::io::read(&self.dummy_fd, &mut buf);
+        Ok(traits::LoopAction::Continue)
+    }
+    fn on_timeout(
+        &mut self,
+        _context: &mut state::DaemonContext,
+    ) -> Result<traits::LoopAction, types::QosError> {
+        self.update();
+        Ok(traits::LoopAction::Continue)
+    }
+    fn get_timeout_ms(&self) -> i32 {
+        INTERVAL_MS
+    }
+    fn get_poll_flags(&self) -> rustix::event::epoll::EventFlags {
+        rustix::event::epoll::EventFlags::empty()
+    }
+}
@@ -1,5 +1,6 @@
 pub mod logging;
 pub mod runtime;
 pub mod state;
+pub mod status;
 pub mod traits;
 pub mod types;
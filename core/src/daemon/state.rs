@@ -1,30 +1,75 @@
 //! Author: [Seclususs](https://github.com/seclususs)
 
-use std::sync;
+use crate::algorithms::thermal_math;
+use crate::hal::{battery, thermal};
+
+use std::{path, sync};
 
 pub static SHUTDOWN_REQUESTED: sync::atomic::AtomicBool = sync::atomic::AtomicBool::new(false);
 pub static BLOCKER_SERVICE_ENABLED: sync::atomic::AtomicBool = sync::atomic::AtomicBool::new(true);
 pub static CLEANER_SERVICE_ENABLED: sync::atomic::AtomicBool = sync::atomic::AtomicBool::new(true);
+pub static CHARGING_SERVICE_ENABLED: sync::atomic::AtomicBool =
+    sync::atomic::AtomicBool::new(false);
 pub static CPU_SERVICE_ENABLED: sync::atomic::AtomicBool = sync::atomic::AtomicBool::new(true);
+pub static CPUSET_SERVICE_ENABLED: sync::atomic::AtomicBool = sync::atomic::AtomicBool::new(true);
+pub static F2FS_SERVICE_ENABLED: sync::atomic::AtomicBool = sync::atomic::AtomicBool::new(false);
 pub static STORAGE_SERVICE_ENABLED: sync::atomic::AtomicBool = sync::atomic::AtomicBool::new(true);
+pub static TRIM_SERVICE_ENABLED: sync::atomic::AtomicBool = sync::atomic::AtomicBool::new(false);
 pub static TWEAKS_ENABLED: sync::atomic::AtomicBool = sync::atomic::AtomicBool::new(true);
+pub static ZRAM_SERVICE_ENABLED: sync::atomic::AtomicBool = sync::atomic::AtomicBool::new(false);
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct GlobalPressure {
     pub cpu_psi: f32,
     pub io_psi: f32,
     pub io_saturation: f32,
+    pub cpu_util: f32,
+    pub cpu_peak_core: f32,
+    pub cpu_iowait: f32,
+    pub cpu_irq: f32,
+    pub cpu_online: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ThermalStatus {
+    pub scale: f32,
+    pub cpu_temp: f32,
+    pub forecast: thermal_math::ThermalForecast,
+    pub sensor_health: thermal::SensorHealth,
+    pub sensor_path: &'static path::Path,
+    pub sensor_failovers: usize,
+}
+
+impl Default for ThermalStatus {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            cpu_temp: 0.0,
+            forecast: thermal_math::ThermalForecast::default(),
+            sensor_health: thermal::SensorHealth::Ok,
+            sensor_path: path::Path::new(""),
+            sensor_failovers: 0,
+        }
+    }
 }
 
 #[derive(Debug, Default)]
 pub struct DaemonContext {
     pub pressure: GlobalPressure,
+    pub thermal: ThermalStatus,
+    pub thermal_zones: Vec<thermal::ZoneReading>,
+    pub cpu_cores: Vec<Option<f32>>,
+    pub power: battery::PowerState,
 }
 
 impl DaemonContext {
     pub fn new() -> Self {
         Self {
             pressure: GlobalPressure::default(),
+            thermal: ThermalStatus::default(),
+            thermal_zones: Vec::new(),
+            cpu_cores: Vec::new(),
+            power: battery::PowerState::default(),
         }
     }
 }
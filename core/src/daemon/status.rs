@@ -0,0 +1,94 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::daemon::state;
+
+use std::fmt::Write;
+use std::{collections, sync};
+
+static SECTIONS: sync::Mutex<collections::BTreeMap<&'static str, String>> =
+    sync::Mutex::new(collections::BTreeMap::new());
+
+pub fn publish(section: &'static str, body: String) {
+    if let Ok(mut sections) = SECTIONS.lock() {
+        sections.insert(section, body);
+    }
+}
+
+pub fn render(context: &state::DaemonContext) -> String {
+    let mut out = String::with_capacity(1024);
+    let pressure = &context.pressure;
+    let _ = writeln!(out, "[pressure]");
+    let _ = writeln!(out, "cpu_psi={:.2}", pressure.cpu_psi);
+    let _ = writeln!(out, "io_psi={:.2}", pressure.io_psi);
+    let _ = writeln!(out, "io_saturation={:.2}", pressure.io_saturation);
+    let _ = writeln!(out, "cpu_util={:.3}", pressure.cpu_util);
+    let _ = writeln!(out, "cpu_peak_core={:.3}", pressure.cpu_peak_core);
+    let _ = writeln!(out, "cpu_iowait={:.3}", pressure.cpu_iowait);
+    let _ = writeln!(out, "cpu_irq={:.3}", pressure.cpu_irq);
+    let _ = writeln!(out, "cpu_online={}", pressure.cpu_online);
+    for (index, busy) in context.cpu_cores.iter().enumerate() {
+        if let Some(busy) = busy {
+            let _ = writeln!(out, "core.{index}={busy:.3}");
+        }
+    }
+    let _ = writeln!(out, "\n[thermal]");
+    let _ = writeln!(out, "scale={:.3}", context.thermal.scale);
+    let _ = writeln!(out, "process_temp={:.1}", context.thermal.cpu_temp);
+    let forecast = &context.thermal.forecast;
+    let _ = writeln!(out, "cpu_steady_state={:.1}", forecast.cpu_steady_state);
+    let _ = writeln!(out, "bat_steady_state={:.1}", forecast.bat_steady_state);
+    match forecast.time_to_throttle() {
+        Some(secs) => {
+            let _ = writeln!(out, "time_to_throttle={secs:.0}s");
+        }
+        None => {
+            let _ = writeln!(out, "time_to_throttle=none");
+        }
+    }
+    let _ = writeln!(
+        out,
+        "cpu_sensor={} ({}, failovers={})",
+        context.thermal.sensor_path.display(),
+        context.thermal.sensor_health.as_str(),
+        context.thermal.sensor_failovers
+    );
+    for zone in &context.thermal_zones {
+        let _ = writeln!(
+            out,
+            "zone.{}={:.1} ({}, {})",
+            zone.name,
+            zone.temp,
+            zone.class.as_str(),
+            zone.health.as_str()
+        );
+    }
+    let power = &context.power;
+    let _ = writeln!(out, "\n[power]");
+    let _ = writeln!(out, "status={}", power.status.as_str());
+    let _ = writeln!(out, "capacity={:.0}", power.capacity);
+    let _ = writeln!(out, "power_mw={:.0}", power.power_mw);
+    let _ = writeln!(out, "smoothed_mw={:.0}", power.smoothed_mw);
+    let _ = writeln!(
+        out,
+        "discharge_pct_per_hour={:.1}",
+        power.discharge_pct_per_hour
+    );
+    match power.time_to_empty_min {
+        Some(minutes) => {
+            let _ = writeln!(out, "time_to_empty={minutes:.0}min");
+        }
+        None => {
+            let _ = writeln!(out, "time_to_empty=none");
+        }
+    }
+    if let Ok(sections) = SECTIONS.lock() {
+        for (name, body) in sections.iter() {
+            let _ = writeln!(out, "\n[{name}]");
+            out.push_str(body);
+            if !body.ends_with('\n') {
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
@@ -1,11 +1,12 @@
 //! Author: [Seclususs](https://github.com/seclususs)
 
 use crate::daemon::types;
+use crate::resources::sys_paths;
 use crate::utils::strings;
 
 use std::{fs, os, path};
 
-const ALLOWED_PREFIXES: [&str; 2] = ["/proc/", "/sys/"];
+const ALLOWED_PREFIXES: [&str; 4] = ["/proc/", "/sys/", "/dev/cpuctl/", "/dev/cpuset/"];
 
 fn validate_path_secure(path_str: &str) -> Result<(), types::QosError> {
     let path = path::Path::new(path_str);
@@ -35,6 +36,10 @@ pub fn open_file_for_write(path: &str) -> Result<fs::File, types::QosError> {
         .map_err(types::QosError::IoError)
 }
 
+pub fn open_first_for_write(paths: &[&str]) -> Option<fs::File> {
+    paths.iter().find_map(|path| open_file_for_write(path).ok())
+}
+
 pub fn open_file_for_read(path: &str) -> Result<fs::File, types::QosError> {
     validate_path_secure(path)?;
     fs::OpenOptions::new()
@@ -100,3 +105,22 @@ pub fn write_to_file(path: &str, value: &str) -> Result<(), types::QosError> {
     })?;
     Ok(())
 }
+
+pub fn write_state_file(path: &str, body: &str) -> Result<(), types::QosError> {
+    let target = path::Path::new(path);
+    if !target.starts_with(sys_paths::K_STATE_DIR)
+        || target
+            .components()
+            .any(|c| matches!(c, path::Component::ParentDir))
+    {
+        return Err(types::QosError::PermissionDenied(format!(
+            "State file outside {}: {path}",
+            sys_paths::K_STATE_DIR
+        )));
+    }
+    fs::create_dir_all(sys_paths::K_STATE_DIR)?;
+    let tmp = target.with_extension("tmp");
+    fs::write(&tmp, body)?;
+    fs::rename(&tmp, target)?;
+    Ok(())
+}
@@ -4,7 +4,7 @@ use crate::bindings::sys;
 use crate::daemon::types;
 use crate::utils::strings;
 
-use std::io;
+use std::{fs, io};
 
 pub fn register_psi_trigger(
     path: &str,
@@ -19,3 +19,18 @@ pub fn register_psi_trigger(
         Ok(fd)
     }
 }
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FstrimRange {
+    pub start: u64,
+    pub len: u64,
+    pub minlen: u64,
+}
+
+const FITRIM: rustix::ioctl::Opcode = rustix::ioctl::opcode::read_write::<FstrimRange>(b'X', 121);
+
+pub fn fitrim(dir: &fs::File, range: &mut FstrimRange) -> Result<(), types::QosError> {
+    let request = unsafe { rustix::ioctl::Updater::<FITRIM, FstrimRange>::new(range) };
+    unsafe { rustix::ioctl::ioctl(dir, request) }.map_err(|e| types::QosError::IoError(e.into()))
+}
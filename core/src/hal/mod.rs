@@ -3,5 +3,6 @@ pub mod bridge;
 pub mod filesystem;
 pub mod kernel;
 pub mod properties;
+pub mod screen;
 pub mod thermal;
 pub mod traversal;
@@ -0,0 +1,31 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::resources::sys_paths;
+use crate::utils::monitored_file;
+
+pub struct ScreenSensor {
+    monitor: Option<monitored_file::MonitoredFile<16>>,
+}
+
+impl ScreenSensor {
+    pub fn new() -> Self {
+        let monitor = sys_paths::get_backlight_path()
+            .and_then(|p| p.to_str())
+            .and_then(|p| monitored_file::MonitoredFile::new(p).ok());
+        Self { monitor }
+    }
+    pub fn is_available(&self) -> bool {
+        self.monitor.is_some()
+    }
+    pub fn is_on(&mut self) -> Option<bool> {
+        let content = self.monitor.as_mut()?.read_value().ok()?;
+        let brightness = content.trim().parse::<u64>().ok()?;
+        Some(brightness > 0)
+    }
+}
+
+impl Default for ScreenSensor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,7 +1,36 @@
 //! Author: [Seclususs](https://github.com/seclususs)
 
+use crate::resources::discovery;
 use crate::utils::monitored_file;
 
+use std::{path, sync, time};
+
+const PLAUSIBLE_MIN: f32 = -20.0;
+const PLAUSIBLE_MAX: f32 = 150.0;
+const MAX_RATE_PER_SEC: f32 = 30.0;
+const MIN_RATE_WINDOW_SEC: f32 = 0.5;
+const ERRATIC_LIMIT: u32 = 5;
+const RECOVERY_SAMPLES: u32 = 3;
+const STUCK_AFTER: time::Duration = time::Duration::from_mins(10);
+const FAILBACK_PROBE: time::Duration = time::Duration::from_mins(1);
+
+static CLOCK_ORIGIN: sync::OnceLock<time::Instant> = sync::OnceLock::new();
+static LAST_MOVEMENT_MS: sync::atomic::AtomicU64 = sync::atomic::AtomicU64::new(0);
+
+fn clock_ms(at: time::Instant) -> u64 {
+    let origin = *CLOCK_ORIGIN.get_or_init(time::Instant::now);
+    u64::try_from(at.saturating_duration_since(origin).as_millis()).unwrap_or(u64::MAX)
+}
+
+fn note_movement(at: time::Instant) {
+    LAST_MOVEMENT_MS.fetch_max(clock_ms(at), sync::atomic::Ordering::Relaxed);
+}
+
+// A flat reading is only suspicious while some other sensor keeps moving.
+fn moved_since(at: time::Instant) -> bool {
+    LAST_MOVEMENT_MS.load(sync::atomic::Ordering::Relaxed) > clock_ms(at)
+}
+
 pub struct ThermalSensor {
     monitor: Option<monitored_file::MonitoredFile<16>>,
     default_val: f32,
@@ -16,9 +45,10 @@ impl ThermalSensor {
         }
     }
     pub fn read(&mut self) -> f32 {
-        let Some(monitor) = self.monitor.as_mut() else {
-            return self.default_val;
-        };
+        self.read_raw().unwrap_or(self.default_val)
+    }
+    pub fn read_raw(&mut self) -> Option<f32> {
+        let monitor = self.monitor.as_mut()?;
         match monitor.read_bytes_raw() {
             Ok(bytes) => {
                 let mut val: i32 = 0;
@@ -37,19 +67,282 @@ impl ThermalSensor {
                     }
                 }
                 if !started {
-                    return self.default_val;
+                    return None;
                 }
                 let final_val = (val * sign) as f32;
                 let abs = final_val.abs();
                 if abs >= 10_000.0 {
-                    final_val / 1000.0
+                    Some(final_val / 1000.0)
                 } else if abs >= 100.0 {
-                    final_val / 10.0
+                    Some(final_val / 10.0)
                 } else {
-                    final_val
+                    Some(final_val)
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SensorHealth {
+    #[default]
+    Ok,
+    Missing,
+    Implausible,
+    Stuck,
+    Erratic,
+}
+
+impl SensorHealth {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SensorHealth::Ok => "ok",
+            SensorHealth::Missing => "missing",
+            SensorHealth::Implausible => "implausible",
+            SensorHealth::Stuck => "stuck",
+            SensorHealth::Erratic => "erratic",
+        }
+    }
+}
+
+struct SensorGuard {
+    health: SensorHealth,
+    last_value: Option<f32>,
+    last_sample: time::Instant,
+    last_change: time::Instant,
+    rejects: u32,
+    clean_streak: u32,
+}
+
+impl SensorGuard {
+    fn new() -> Self {
+        let now = time::Instant::now();
+        Self {
+            health: SensorHealth::Ok,
+            last_value: None,
+            last_sample: now,
+            last_change: now,
+            rejects: 0,
+            clean_streak: 0,
+        }
+    }
+    fn fault(&mut self, health: SensorHealth) {
+        if self.health != health {
+            log::debug!(
+                "ThermalSensor: Health {} -> {}",
+                self.health.as_str(),
+                health.as_str()
+            );
+        }
+        self.health = health;
+        self.clean_streak = 0;
+    }
+    fn check(&mut self, raw: Option<f32>, now: time::Instant) -> Option<f32> {
+        let Some(value) = raw else {
+            self.fault(SensorHealth::Missing);
+            return None;
+        };
+        if !(PLAUSIBLE_MIN..=PLAUSIBLE_MAX).contains(&value) || value == 0.0 {
+            self.fault(SensorHealth::Implausible);
+            return None;
+        }
+        if let Some(last) = self.last_value {
+            let dt = now
+                .duration_since(self.last_sample)
+                .as_secs_f32()
+                .max(MIN_RATE_WINDOW_SEC);
+            if (value - last).abs() > MAX_RATE_PER_SEC * dt {
+                self.rejects += 1;
+                if self.rejects < ERRATIC_LIMIT {
+                    return (self.health == SensorHealth::Ok).then_some(last);
+                }
+                self.fault(SensorHealth::Erratic);
+                self.rejects = 0;
+                self.last_value = Some(value);
+                self.last_sample = now;
+                self.last_change = now;
+                return None;
+            }
+            if (value - last).abs() > f32::EPSILON {
+                self.last_change = now;
+                note_movement(now);
+            } else if now.duration_since(self.last_change) >= STUCK_AFTER
+                && moved_since(self.last_change)
+            {
+                self.fault(SensorHealth::Stuck);
+                self.last_sample = now;
+                return None;
+            }
+        } else {
+            self.last_change = now;
+        }
+        self.rejects = 0;
+        self.last_value = Some(value);
+        self.last_sample = now;
+        if self.health != SensorHealth::Ok {
+            self.clean_streak += 1;
+            if self.clean_streak < RECOVERY_SAMPLES {
+                return None;
+            }
+            self.health = SensorHealth::Ok;
+        }
+        Some(value)
+    }
+}
+
+struct GuardedCandidate {
+    path: &'static path::Path,
+    sensor: ThermalSensor,
+    guard: SensorGuard,
+}
+
+pub struct GuardedThermalSensor {
+    candidates: Vec<GuardedCandidate>,
+    active: usize,
+    failovers: usize,
+    default_val: f32,
+    last_good: Option<f32>,
+    last_probe: time::Instant,
+}
+
+impl GuardedThermalSensor {
+    pub fn new(paths: &'static [path::PathBuf], default_val: f32) -> Self {
+        let candidates = paths
+            .iter()
+            .filter_map(|p| {
+                Some(GuardedCandidate {
+                    path: p.as_path(),
+                    sensor: ThermalSensor::new(p.to_str()?, default_val),
+                    guard: SensorGuard::new(),
+                })
+            })
+            .collect();
+        Self {
+            candidates,
+            active: 0,
+            failovers: 0,
+            default_val,
+            last_good: None,
+            last_probe: time::Instant::now(),
+        }
+    }
+    pub fn read(&mut self, now: time::Instant) -> f32 {
+        self.read_checked(now)
+            .or(self.last_good)
+            .unwrap_or(self.default_val)
+    }
+    pub fn read_checked(&mut self, now: time::Instant) -> Option<f32> {
+        if self.active > 0 && now.duration_since(self.last_probe) >= FAILBACK_PROBE {
+            self.last_probe = now;
+            self.probe_preferred(now);
+        }
+        let start = self.active;
+        while let Some(candidate) = self.candidates.get_mut(self.active) {
+            let raw = candidate.sensor.read_raw();
+            if let Some(value) = candidate.guard.check(raw, now) {
+                if self.active != start {
+                    self.failovers += 1;
                 }
+                self.last_good = Some(value);
+                return Some(value);
+            }
+            if candidate.guard.health == SensorHealth::Ok {
+                break;
+            }
+            log::warn!(
+                "ThermalSensor: {} is {}, failing over.",
+                candidate.path.display(),
+                candidate.guard.health.as_str()
+            );
+            self.active += 1;
+            self.last_probe = now;
+        }
+        // Wrapping around with every candidate faulty is not a switch.
+        if self.active >= self.candidates.len() {
+            self.active = 0;
+        } else if self.active != start {
+            self.failovers += 1;
+        }
+        None
+    }
+    fn probe_preferred(&mut self, now: time::Instant) {
+        for index in 0..self.active {
+            let candidate = &mut self.candidates[index];
+            let raw = candidate.sensor.read_raw();
+            if candidate.guard.check(raw, now).is_some() {
+                log::info!(
+                    "ThermalSensor: {} recovered, failing back.",
+                    candidate.path.display()
+                );
+                self.active = index;
+                return;
             }
-            _ => self.default_val,
+        }
+    }
+    pub fn health(&self) -> SensorHealth {
+        self.candidates
+            .get(self.active)
+            .map_or(SensorHealth::Missing, |c| c.guard.health)
+    }
+    pub fn active_path(&self) -> &'static path::Path {
+        self.candidates
+            .get(self.active)
+            .map_or(path::Path::new(""), |c| c.path)
+    }
+    pub fn failovers(&self) -> usize {
+        self.failovers
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ZoneReading {
+    pub name: &'static str,
+    pub class: discovery::ZoneClass,
+    pub temp: f32,
+    pub health: SensorHealth,
+}
+
+struct ZoneSensor {
+    zone: &'static discovery::ThermalZone,
+    sensor: ThermalSensor,
+    guard: SensorGuard,
+}
+
+pub struct ThermalZoneSet {
+    sensors: Vec<ZoneSensor>,
+}
+
+impl ThermalZoneSet {
+    pub fn new(zones: &'static [discovery::ThermalZone]) -> Self {
+        let sensors = zones
+            .iter()
+            .filter_map(|zone| {
+                let sensor = ThermalSensor::new(zone.temp_path.to_str()?, 0.0);
+                sensor.monitor.is_some().then_some(ZoneSensor {
+                    zone,
+                    sensor,
+                    guard: SensorGuard::new(),
+                })
+            })
+            .collect();
+        Self { sensors }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.sensors.is_empty()
+    }
+    pub fn read_into(&mut self, readings: &mut Vec<ZoneReading>) {
+        let now = time::Instant::now();
+        readings.clear();
+        for entry in &mut self.sensors {
+            let raw = entry.sensor.read_raw();
+            let temp = entry.guard.check(raw, now).unwrap_or(0.0);
+            readings.push(ZoneReading {
+                name: entry.zone.name.as_str(),
+                class: entry.zone.class,
+                temp,
+                health: entry.guard.health,
+            });
         }
     }
 }
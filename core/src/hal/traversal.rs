@@ -2,6 +2,8 @@
 
 use std::{fs, path};
 
+const STOP_CHECK_INTERVAL: usize = 256;
+
 pub enum TraversalAction {
     Keep,
     DeleteFile,
@@ -31,26 +33,50 @@ pub fn get_tree_size_capped(path: &path::Path, limit: u64) -> u64 {
     size
 }
 
-pub fn walk_and_act<F>(dir: &path::Path, callback: &F, depth: usize) -> usize
+pub trait Visitor {
+    fn visit(&mut self, entry: &fs::DirEntry, depth: usize) -> TraversalAction;
+    fn removed(&mut self, _entry: &fs::DirEntry) {}
+    fn should_stop(&mut self) -> bool {
+        false
+    }
+}
+
+impl<F> Visitor for F
 where
-    F: Fn(&fs::DirEntry, usize) -> TraversalAction,
+    F: FnMut(&fs::DirEntry, usize) -> TraversalAction,
+{
+    fn visit(&mut self, entry: &fs::DirEntry, depth: usize) -> TraversalAction {
+        self(entry, depth)
+    }
+}
+
+pub fn walk_and_act<V>(dir: &path::Path, visitor: &mut V, depth: usize) -> usize
+where
+    V: Visitor,
 {
     if depth > 20 {
         return 0;
     }
     let mut count = 0;
     if let Ok(entries) = fs::read_dir(dir) {
-        for entry in entries.flatten() {
+        for (index, entry) in entries.flatten().enumerate() {
+            if index > 0 && index % STOP_CHECK_INTERVAL == 0 && visitor.should_stop() {
+                return count;
+            }
             if let Ok(ft) = entry.file_type() {
                 if ft.is_symlink() {
                     continue;
                 }
                 if ft.is_dir() {
-                    count += walk_and_act(&entry.path(), callback, depth + 1);
+                    if visitor.should_stop() {
+                        return count;
+                    }
+                    count += walk_and_act(&entry.path(), visitor, depth + 1);
                 } else {
-                    match callback(&entry, depth) {
+                    match visitor.visit(&entry, depth) {
                         TraversalAction::DeleteFile => {
                             if fs::remove_file(entry.path()).is_ok() {
+                                visitor.removed(&entry);
                                 count += 1;
                             }
                         }
@@ -63,3 +89,68 @@ where
     }
     count
 }
+
+pub fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => {
+                let Some((star, matched)) = backtrack else {
+                    return false;
+                };
+                p = star + 1;
+                t = matched + 1;
+                backtrack = Some((star, matched + 1));
+            }
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+pub fn expand_glob(pattern: &str) -> Vec<(path::PathBuf, Option<String>)> {
+    let mut matches: Vec<(path::PathBuf, Option<String>)> = vec![(path::PathBuf::from("/"), None)];
+    let mut expanded = false;
+    for segment in pattern.split('/').filter(|s| !s.is_empty()) {
+        if !segment.contains(['*', '?']) {
+            for (dir, _) in &mut matches {
+                dir.push(segment);
+            }
+            // Below a wildcard the tree belongs to apps, which could swap a
+            // directory for a symlink pointing anywhere.
+            if expanded {
+                matches.retain(|(dir, _)| {
+                    fs::symlink_metadata(dir).is_ok_and(|meta| meta.file_type().is_dir())
+                });
+            }
+            continue;
+        }
+        expanded = true;
+        let mut next = Vec::with_capacity(matches.len());
+        for (dir, _) in &matches {
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                if !entry.file_type().is_ok_and(|ft| ft.is_dir()) {
+                    continue;
+                }
+                let name = entry.file_name();
+                if glob_match(segment.as_bytes(), name.as_encoded_bytes()) {
+                    next.push((entry.path(), name.to_str().map(str::to_string)));
+                }
+            }
+        }
+        matches = next;
+    }
+    matches.retain(|(dir, _)| dir.is_dir());
+    matches
+}
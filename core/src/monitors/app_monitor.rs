@@ -0,0 +1,220 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::resources::sys_paths;
+
+use std::{collections, fs, os, path, time};
+
+pub const K_PER_USER_RANGE: u32 = 100_000;
+const K_FIRST_APP_UID: u32 = 10_000;
+
+#[derive(Debug, Clone)]
+pub struct PackageInfo {
+    pub name: String,
+    pub app_id: u32,
+}
+
+pub fn load_packages() -> Vec<PackageInfo> {
+    let Ok(content) = fs::read_to_string(sys_paths::K_PACKAGES_LIST) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_ascii_whitespace();
+            let name = fields.next()?;
+            let uid: u32 = fields.next()?.parse().ok()?;
+            Some(PackageInfo {
+                name: name.to_string(),
+                app_id: uid % K_PER_USER_RANGE,
+            })
+        })
+        .collect()
+}
+
+// Package settings the system still holds, including packages missing from
+// packages.list and those uninstalled with their data kept.
+#[derive(Debug, Default)]
+pub struct PackageRecords {
+    pub names: collections::HashSet<String>,
+    pub code_paths: Vec<path::PathBuf>,
+}
+
+fn xml_attr<'a>(tag: &'a str, attr: &str) -> Option<&'a str> {
+    let start = tag.find(&format!(" {attr}=\""))? + attr.len() + 3;
+    let (value, _) = tag[start..].split_once('"')?;
+    Some(value)
+}
+
+pub fn load_package_records() -> Option<PackageRecords> {
+    let raw = fs::read(sys_paths::K_PACKAGES_XML).ok()?;
+    // Android 12+ may store this file as binary XML, which is not parsed here.
+    let content = std::str::from_utf8(&raw).ok()?;
+    if !content.trim_start().starts_with('<') {
+        return None;
+    }
+    let mut records = PackageRecords::default();
+    for tag in content.split('<') {
+        if let Some(code_path) = xml_attr(tag, "codePath") {
+            records.code_paths.push(path::PathBuf::from(code_path));
+        }
+        if (tag.starts_with("package ") || tag.starts_with("updated-package "))
+            && let Some(name) = xml_attr(tag, "name")
+        {
+            records.names.insert(name.to_string());
+        }
+    }
+    Some(records)
+}
+
+fn numeric_entries(dir: &str, suffix: &str) -> Vec<u32> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            name.to_str()?.strip_suffix(suffix)?.parse().ok()
+        })
+        .collect()
+}
+
+pub fn list_users() -> Vec<u32> {
+    let mut users = numeric_entries(sys_paths::K_SYSTEM_USERS_DIR, ".xml");
+    users.extend(numeric_entries(sys_paths::K_DATA_USER_DIR, ""));
+    users.push(0);
+    users.sort_unstable();
+    users.dedup();
+    users
+}
+
+fn process_name(pid: &str) -> Option<String> {
+    let raw = fs::read(
+        path::Path::new(sys_paths::K_PROC_DIR)
+            .join(pid)
+            .join("cmdline"),
+    )
+    .ok()?;
+    let first = raw.split(|&b| b == 0).next()?;
+    let name = std::str::from_utf8(first).ok()?;
+    let name = name.rsplit('/').next().unwrap_or(name);
+    let name = name.split(':').next().unwrap_or(name);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn process_uid(pid: &str) -> Option<u32> {
+    let meta = fs::metadata(path::Path::new(sys_paths::K_PROC_DIR).join(pid)).ok()?;
+    Some(os::unix::fs::MetadataExt::uid(&meta))
+}
+
+pub struct RunningApps {
+    pids: collections::HashMap<(u32, String), Vec<String>>,
+}
+
+impl RunningApps {
+    pub fn scan(packages: &[PackageInfo]) -> Self {
+        let mut by_name: collections::HashSet<&str> = collections::HashSet::new();
+        let mut by_app_id: collections::HashMap<u32, Vec<&str>> = collections::HashMap::new();
+        for package in packages {
+            by_name.insert(&package.name);
+            by_app_id
+                .entry(package.app_id)
+                .or_default()
+                .push(&package.name);
+        }
+        let mut pids: collections::HashMap<(u32, String), Vec<String>> =
+            collections::HashMap::new();
+        let Ok(entries) = fs::read_dir(sys_paths::K_PROC_DIR) else {
+            return Self { pids };
+        };
+        for entry in entries.flatten() {
+            let Some(pid) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            if !pid.bytes().all(|b| b.is_ascii_digit()) {
+                continue;
+            }
+            let Some(uid) = process_uid(&pid) else {
+                continue;
+            };
+            let user = uid / K_PER_USER_RANGE;
+            let app_id = uid % K_PER_USER_RANGE;
+            let named = process_name(&pid).filter(|name| by_name.contains(name.as_str()));
+            let owners: Vec<String> = match named {
+                Some(name) => vec![name],
+                None if app_id >= K_FIRST_APP_UID => by_app_id
+                    .get(&app_id)
+                    .map(|names| names.iter().map(|n| (*n).to_string()).collect())
+                    .unwrap_or_default(),
+                None => Vec::new(),
+            };
+            for owner in owners {
+                pids.entry((user, owner)).or_default().push(pid.clone());
+            }
+        }
+        Self { pids }
+    }
+    pub fn is_running(&self, user: u32, package: &str) -> bool {
+        self.pids.contains_key(&(user, package.to_string()))
+    }
+    pub fn count(&self) -> usize {
+        self.pids.len()
+    }
+    pub fn open_files(&self, user: u32, package: &str) -> collections::HashSet<(u64, u64)> {
+        let mut open = collections::HashSet::new();
+        for pid in self
+            .pids
+            .get(&(user, package.to_string()))
+            .into_iter()
+            .flatten()
+        {
+            let fd_dir = path::Path::new(sys_paths::K_PROC_DIR).join(pid).join("fd");
+            let Ok(entries) = fs::read_dir(fd_dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                if let Ok(meta) = fs::metadata(entry.path()) {
+                    open.insert((
+                        os::unix::fs::MetadataExt::dev(&meta),
+                        os::unix::fs::MetadataExt::ino(&meta),
+                    ));
+                }
+            }
+        }
+        open
+    }
+}
+
+pub struct ForegroundTracker {
+    last_seen: collections::HashMap<String, time::Instant>,
+}
+
+impl ForegroundTracker {
+    pub fn new() -> Self {
+        Self {
+            last_seen: collections::HashMap::new(),
+        }
+    }
+    pub fn sample(&mut self, now: time::Instant, window: time::Duration) {
+        let content = sys_paths::K_TOP_APP_PROCS_CANDIDATES
+            .iter()
+            .find_map(|path| fs::read_to_string(path).ok())
+            .unwrap_or_default();
+        for pid in content.lines().map(str::trim).filter(|p| !p.is_empty()) {
+            if let Some(name) = process_name(pid) {
+                self.last_seen.insert(name, now);
+            }
+        }
+        self.last_seen
+            .retain(|_, seen| now.duration_since(*seen) <= window);
+    }
+    pub fn recent(&self) -> Vec<String> {
+        self.last_seen.keys().cloned().collect()
+    }
+}
+
+impl Default for ForegroundTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -3,6 +3,9 @@
 use crate::daemon::types;
 use crate::utils::monitored_file;
 
+const MIN_FIELDS: usize = 11;
+const MAX_FIELDS: usize = 17;
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct IoStats {
     pub read_ios: u64,
@@ -10,8 +13,42 @@ pub struct IoStats {
     pub read_sectors: u64,
     pub read_ticks: u64,
     pub write_ios: u64,
+    pub write_sectors: u64,
     pub write_ticks: u64,
     pub in_flight: u64,
+    pub discard_ios: u64,
+    pub discard_sectors: u64,
+    pub discard_ticks: u64,
+    pub flush_ios: u64,
+    pub flush_ticks: u64,
+    pub field_count: usize,
+}
+
+impl IoStats {
+    pub fn has_discard(&self) -> bool {
+        self.field_count >= 15
+    }
+    pub fn has_flush(&self) -> bool {
+        self.field_count >= 17
+    }
+    fn from_fields(fields: &[u64; MAX_FIELDS], field_count: usize) -> Self {
+        Self {
+            read_ios: fields[0],
+            read_merges: fields[1],
+            read_sectors: fields[2],
+            read_ticks: fields[3],
+            write_ios: fields[4],
+            write_sectors: fields[6],
+            write_ticks: fields[7],
+            in_flight: fields[8],
+            discard_ios: fields[11],
+            discard_sectors: fields[13],
+            discard_ticks: fields[14],
+            flush_ios: fields[15],
+            flush_ticks: fields[16],
+            field_count,
+        }
+    }
 }
 
 pub struct DiskMonitor {
@@ -29,47 +66,27 @@ impl DiskMonitor {
         if buffer.is_empty() {
             return Err(types::QosError::SystemCheckFailed("Empty diskstats".into()));
         }
-        let mut stats = IoStats::default();
-        let mut field_idx = 0;
-        let mut cursor = 0;
-        while cursor < buffer.len() {
-            while cursor < buffer.len()
-                && (buffer[cursor] == b' ' || buffer[cursor] == b'\t' || buffer[cursor] == b'\n')
-            {
-                cursor += 1;
-            }
-            if cursor >= buffer.len() {
-                break;
-            }
-            let mut val: u64 = 0;
-            let start = cursor;
-            while cursor < buffer.len() && buffer[cursor].is_ascii_digit() {
-                val = val * 10 + u64::from(buffer[cursor] - b'0');
-                cursor += 1;
+        let line = buffer.split(|&b| b == b'\n').next().unwrap_or_default();
+        let mut fields = [0u64; MAX_FIELDS];
+        let mut count = 0;
+        for token in line
+            .split(u8::is_ascii_whitespace)
+            .filter(|t| !t.is_empty())
+        {
+            if !token.iter().all(u8::is_ascii_digit) {
+                fields = [0u64; MAX_FIELDS];
+                count = 0;
+                continue;
             }
-            if cursor > start {
-                match field_idx {
-                    0 => stats.read_ios = val,
-                    1 => stats.read_merges = val,
-                    2 => stats.read_sectors = val,
-                    3 => stats.read_ticks = val,
-                    4 => stats.write_ios = val,
-                    7 => stats.write_ticks = val,
-                    8 => stats.in_flight = val,
-                    _ => {}
-                }
-                field_idx += 1;
-                if field_idx > 8 {
-                    break;
-                }
-            } else {
-                while cursor < buffer.len() && !buffer[cursor].is_ascii_whitespace() {
-                    cursor += 1;
-                }
+            if count < MAX_FIELDS {
+                fields[count] = token.iter().fold(0u64, |acc, &b| {
+                    acc.wrapping_mul(10).wrapping_add(u64::from(b - b'0'))
+                });
             }
+            count += 1;
         }
-        if field_idx > 8 {
-            Ok(stats)
+        if count >= MIN_FIELDS {
+            Ok(IoStats::from_fields(&fields, count.min(MAX_FIELDS)))
         } else {
             Err(types::QosError::SystemCheckFailed(
                 "Incomplete diskstats".into(),
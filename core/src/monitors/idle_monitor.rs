@@ -0,0 +1,58 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::hal::{battery, screen, thermal};
+use crate::resources::sys_paths;
+
+#[derive(Debug, Clone, Copy)]
+pub struct IdleConditions {
+    pub screen_on: bool,
+    pub charging: bool,
+    pub battery_temp: f32,
+}
+
+impl IdleConditions {
+    pub fn blocker(&self, temp_ceiling: f32) -> Option<&'static str> {
+        if self.screen_on {
+            Some("screen_on")
+        } else if !self.charging {
+            Some("not_charging")
+        } else if self.battery_temp > temp_ceiling {
+            Some("too_warm")
+        } else {
+            None
+        }
+    }
+}
+
+pub struct IdleMonitor {
+    screen: screen::ScreenSensor,
+    charge: battery::ChargeStatusSensor,
+    temp: thermal::ThermalSensor,
+}
+
+impl IdleMonitor {
+    pub fn new() -> Self {
+        let screen = screen::ScreenSensor::new();
+        if !screen.is_available() {
+            log::warn!("IdleMonitor: No backlight node; idle maintenance will not run.");
+        }
+        Self {
+            screen,
+            charge: battery::ChargeStatusSensor::new(),
+            temp: thermal::ThermalSensor::new(sys_paths::K_BATTERY_TEMP_PATH, 99.0),
+        }
+    }
+    pub fn sample(&mut self) -> IdleConditions {
+        IdleConditions {
+            screen_on: self.screen.is_on().unwrap_or(true),
+            charging: self.charge.read().on_external_power(),
+            battery_temp: self.temp.read(),
+        }
+    }
+}
+
+impl Default for IdleMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
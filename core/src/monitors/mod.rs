@@ -1,2 +1,7 @@
+pub mod app_monitor;
 pub mod disk_monitor;
+pub mod idle_monitor;
+pub mod orphan_monitor;
 pub mod psi_monitor;
+pub mod stat_monitor;
+pub mod zram_monitor;
@@ -0,0 +1,186 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::monitors::app_monitor;
+use crate::resources::sys_paths;
+
+use std::{collections, fs, path};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrphanKind {
+    AppData,
+    Obb,
+    AppCode,
+    DexCache,
+}
+
+impl OrphanKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OrphanKind::AppData => "data",
+            OrphanKind::Obb => "obb",
+            OrphanKind::AppCode => "app",
+            OrphanKind::DexCache => "dalvik",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Orphan {
+    pub kind: OrphanKind,
+    pub package: String,
+    pub path: path::PathBuf,
+    source: path::PathBuf,
+}
+
+impl Orphan {
+    fn new(kind: OrphanKind, package: String, path: path::PathBuf) -> Self {
+        Self {
+            kind,
+            package,
+            source: path.clone(),
+            path,
+        }
+    }
+    pub fn is_removable(&self, records: Option<&app_monitor::PackageRecords>) -> bool {
+        let Some(records) = records else {
+            return false;
+        };
+        match self.kind {
+            OrphanKind::AppData | OrphanKind::Obb => !records.names.contains(&self.package),
+            OrphanKind::AppCode | OrphanKind::DexCache => !records
+                .code_paths
+                .iter()
+                .any(|code| code.starts_with(&self.source) || self.source.starts_with(code)),
+        }
+    }
+}
+
+fn is_package_name(name: &str) -> bool {
+    name.contains('.')
+        && !name.starts_with('.')
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'.' || b == b'_')
+}
+
+fn code_dir_package(name: &str) -> Option<&str> {
+    let (package, _) = name.split_once('-')?;
+    is_package_name(package).then_some(package)
+}
+
+fn read_subdirs(dir: &path::Path) -> Option<Vec<(String, path::PathBuf)>> {
+    let entries = fs::read_dir(dir).ok()?;
+    Some(
+        entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|ft| ft.is_dir()))
+            .filter_map(|entry| Some((entry.file_name().to_str()?.to_string(), entry.path())))
+            .collect(),
+    )
+}
+
+fn subdirs(dir: &path::Path) -> Vec<(String, path::PathBuf)> {
+    read_subdirs(dir).unwrap_or_default()
+}
+
+fn scan_package_dirs(
+    dir: &path::Path,
+    kind: OrphanKind,
+    installed: &collections::HashSet<&str>,
+    found: &mut Vec<Orphan>,
+) {
+    for (name, path) in subdirs(dir) {
+        if is_package_name(&name) && !installed.contains(name.as_str()) {
+            found.push(Orphan::new(kind, name, path));
+        }
+    }
+}
+
+fn scan_app_dirs(installed: &collections::HashSet<&str>, found: &mut Vec<Orphan>) {
+    for (name, path) in subdirs(path::Path::new(sys_paths::K_DATA_APP_DIR)) {
+        if !name.starts_with("~~") {
+            if let Some(package) = code_dir_package(&name)
+                && !installed.contains(package)
+            {
+                found.push(Orphan::new(OrphanKind::AppCode, package.to_string(), path));
+            }
+            continue;
+        }
+        // Since Android 11 every install sits in its own randomly named parent.
+        let Some(children) = read_subdirs(&path).filter(|c| !c.is_empty()) else {
+            continue;
+        };
+        let packages: Vec<Option<&str>> = children
+            .iter()
+            .map(|(child, _)| code_dir_package(child))
+            .collect();
+        if let [Some(first), ..] = packages[..]
+            && packages
+                .iter()
+                .all(|package| package.is_some_and(|p| !installed.contains(p)))
+        {
+            found.push(Orphan::new(OrphanKind::AppCode, first.to_string(), path));
+        }
+    }
+}
+
+fn dex_source(name: &str) -> Option<path::PathBuf> {
+    let encoded = name.strip_prefix("data@app@")?;
+    let (apk, _) = encoded.rsplit_once('@')?;
+    Some(path::Path::new(sys_paths::K_DATA_APP_DIR).join(apk.replace('@', "/")))
+}
+
+fn scan_dalvik_cache(found: &mut Vec<Orphan>) {
+    for (_, isa_dir) in subdirs(path::Path::new(sys_paths::K_DALVIK_CACHE_DIR)) {
+        let Ok(entries) = fs::read_dir(&isa_dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if !entry.file_type().is_ok_and(|ft| ft.is_file()) {
+                continue;
+            }
+            let Some(source) = entry.file_name().to_str().and_then(dex_source) else {
+                continue;
+            };
+            if source.exists() {
+                continue;
+            }
+            let package = source
+                .iter()
+                .find_map(|c| c.to_str().and_then(code_dir_package))
+                .unwrap_or("-")
+                .to_string();
+            found.push(Orphan {
+                kind: OrphanKind::DexCache,
+                package,
+                path: entry.path(),
+                source,
+            });
+        }
+    }
+}
+
+pub fn scan(packages: &[app_monitor::PackageInfo], users: &[u32]) -> Vec<Orphan> {
+    let installed: collections::HashSet<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+    let mut found = Vec::new();
+    let media = path::Path::new(sys_paths::K_DATA_MEDIA_DIR);
+    for user in users {
+        let android = media.join(user.to_string()).join("Android");
+        scan_package_dirs(
+            &android.join("data"),
+            OrphanKind::AppData,
+            &installed,
+            &mut found,
+        );
+        scan_package_dirs(
+            &android.join("obb"),
+            OrphanKind::Obb,
+            &installed,
+            &mut found,
+        );
+    }
+    scan_package_dirs(&media.join("obb"), OrphanKind::Obb, &installed, &mut found);
+    scan_app_dirs(&installed, &mut found);
+    scan_dalvik_cache(&mut found);
+    found
+}
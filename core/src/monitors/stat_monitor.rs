@@ -0,0 +1,190 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::daemon::types;
+use crate::utils::monitored_file;
+
+const MAX_CPUS: usize = 16;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct CpuTimes {
+    user: u64,
+    nice: u64,
+    system: u64,
+    idle: u64,
+    iowait: u64,
+    irq: u64,
+    softirq: u64,
+    steal: u64,
+}
+
+impl CpuTimes {
+    #[inline]
+    fn total(&self) -> u64 {
+        self.user
+            + self.nice
+            + self.system
+            + self.idle
+            + self.iowait
+            + self.irq
+            + self.softirq
+            + self.steal
+    }
+    #[inline]
+    fn idle_all(&self) -> u64 {
+        self.idle + self.iowait
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CpuUtilization {
+    pub aggregate: f32,
+    pub peak_core: f32,
+    pub iowait: f32,
+    pub irq_share: f32,
+    pub online_cpus: usize,
+}
+
+pub struct StatMonitor {
+    monitor: monitored_file::MonitoredFile<4096>,
+    prev_total: CpuTimes,
+    prev_cores: [CpuTimes; MAX_CPUS],
+    prev_online: [bool; MAX_CPUS],
+    per_core: [Option<f32>; MAX_CPUS],
+    first_run: bool,
+}
+
+impl StatMonitor {
+    pub fn new(path: &str) -> Result<Self, types::QosError> {
+        Ok(Self {
+            monitor: monitored_file::MonitoredFile::new(path)?,
+            prev_total: CpuTimes::default(),
+            prev_cores: [CpuTimes::default(); MAX_CPUS],
+            prev_online: [false; MAX_CPUS],
+            per_core: [None; MAX_CPUS],
+            first_run: true,
+        })
+    }
+    #[inline]
+    fn parse_times(line: &[u8]) -> CpuTimes {
+        let mut fields = [0u64; 8];
+        let mut field_idx = 0;
+        let mut cursor = 0;
+        while cursor < line.len() && !line[cursor].is_ascii_whitespace() {
+            cursor += 1;
+        }
+        while cursor < line.len() && field_idx < fields.len() {
+            while cursor < line.len() && line[cursor] == b' ' {
+                cursor += 1;
+            }
+            let start = cursor;
+            let mut val = 0u64;
+            while cursor < line.len() && line[cursor].is_ascii_digit() {
+                val = val * 10 + u64::from(line[cursor] - b'0');
+                cursor += 1;
+            }
+            if cursor == start {
+                break;
+            }
+            fields[field_idx] = val;
+            field_idx += 1;
+        }
+        CpuTimes {
+            user: fields[0],
+            nice: fields[1],
+            system: fields[2],
+            idle: fields[3],
+            iowait: fields[4],
+            irq: fields[5],
+            softirq: fields[6],
+            steal: fields[7],
+        }
+    }
+    #[inline]
+    fn parse_cpu_index(line: &[u8]) -> Option<usize> {
+        let mut idx = 3;
+        let mut val = 0usize;
+        while idx < line.len() && line[idx].is_ascii_digit() {
+            val = val * 10 + usize::from(line[idx] - b'0');
+            idx += 1;
+        }
+        (idx > 3).then_some(val)
+    }
+    #[inline]
+    fn busy_ratio(current: &CpuTimes, prev: &CpuTimes) -> f32 {
+        let total = current.total().saturating_sub(prev.total());
+        if total == 0 {
+            return 0.0;
+        }
+        let idle = current.idle_all().saturating_sub(prev.idle_all());
+        (total.saturating_sub(idle) as f32 / total as f32).clamp(0.0, 1.0)
+    }
+    pub fn read_state(&mut self) -> Result<CpuUtilization, types::QosError> {
+        let buffer = self.monitor.read_bytes_raw()?;
+        if buffer.is_empty() {
+            return Err(types::QosError::SystemCheckFailed(
+                "Empty /proc/stat".into(),
+            ));
+        }
+        let mut total = CpuTimes::default();
+        let mut cores = [CpuTimes::default(); MAX_CPUS];
+        let mut online = [false; MAX_CPUS];
+        let mut found_total = false;
+        for line in buffer.split(|&b| b == b'\n') {
+            if !line.starts_with(b"cpu") {
+                if found_total {
+                    break;
+                }
+                continue;
+            }
+            if line.get(3) == Some(&b' ') {
+                total = Self::parse_times(line);
+                found_total = true;
+            } else if let Some(idx) = Self::parse_cpu_index(line)
+                && idx < MAX_CPUS
+            {
+                cores[idx] = Self::parse_times(line);
+                online[idx] = true;
+            }
+        }
+        if !found_total {
+            return Err(types::QosError::SystemCheckFailed(
+                "Missing aggregate cpu line".into(),
+            ));
+        }
+        let mut result = CpuUtilization::default();
+        if !self.first_run {
+            let delta_total = total.total().saturating_sub(self.prev_total.total());
+            if delta_total > 0 {
+                let delta = delta_total as f32;
+                result.aggregate = Self::busy_ratio(&total, &self.prev_total);
+                result.iowait = total.iowait.saturating_sub(self.prev_total.iowait) as f32 / delta;
+                let irq_now = total.irq + total.softirq;
+                let irq_prev = self.prev_total.irq + self.prev_total.softirq;
+                result.irq_share = irq_now.saturating_sub(irq_prev) as f32 / delta;
+            }
+        }
+        for idx in 0..MAX_CPUS {
+            self.per_core[idx] = if !online[idx] {
+                None
+            } else if self.prev_online[idx] {
+                Some(Self::busy_ratio(&cores[idx], &self.prev_cores[idx]))
+            } else {
+                // Just came online (or first sample): this reading only seeds
+                // the baseline, otherwise the ratio would span since boot.
+                Some(0.0)
+            };
+            if let Some(busy) = self.per_core[idx] {
+                result.peak_core = result.peak_core.max(busy);
+                result.online_cpus += 1;
+            }
+        }
+        self.prev_total = total;
+        self.prev_cores = cores;
+        self.prev_online = online;
+        self.first_run = false;
+        Ok(result)
+    }
+    pub fn per_core(&self) -> &[Option<f32>] {
+        &self.per_core
+    }
+}
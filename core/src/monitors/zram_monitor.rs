@@ -0,0 +1,97 @@
+//! Author: [Seclususs](https://github.com/seclususs)
+
+use crate::daemon::types;
+use crate::resources::discovery;
+use crate::utils::monitored_file;
+
+const PAGE_SIZE: u64 = 4096;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZramStats {
+    pub disksize: u64,
+    pub orig_data_size: u64,
+    pub compr_data_size: u64,
+    pub mem_used_total: u64,
+    pub mem_limit: u64,
+    pub same_pages: u64,
+    pub huge_pages: u64,
+    pub failed_reads: u64,
+    pub failed_writes: u64,
+}
+
+impl ZramStats {
+    pub fn compression_ratio(&self) -> f32 {
+        if self.compr_data_size == 0 {
+            return 0.0;
+        }
+        self.orig_data_size as f32 / self.compr_data_size as f32
+    }
+    pub fn pages_stored(&self) -> u64 {
+        self.orig_data_size / PAGE_SIZE
+    }
+    pub fn fill(&self) -> f32 {
+        let data_fill = if self.disksize > 0 {
+            self.orig_data_size as f32 / self.disksize as f32
+        } else {
+            0.0
+        };
+        let mem_fill = if self.mem_limit > 0 {
+            self.mem_used_total as f32 / self.mem_limit as f32
+        } else {
+            0.0
+        };
+        data_fill.max(mem_fill).clamp(0.0, 1.0)
+    }
+}
+
+fn parse_fields<const N: usize>(content: &str) -> [u64; N] {
+    let mut fields = [0u64; N];
+    for (slot, value) in fields.iter_mut().zip(content.split_ascii_whitespace()) {
+        *slot = value.parse().unwrap_or(0);
+    }
+    fields
+}
+
+pub struct ZramMonitor {
+    disksize: monitored_file::MonitoredFile<32>,
+    mm_stat: monitored_file::MonitoredFile<256>,
+    io_stat: Option<monitored_file::MonitoredFile<128>>,
+}
+
+impl ZramMonitor {
+    pub fn new(device: &discovery::ZramDevice) -> Result<Self, types::QosError> {
+        let open = |attr: &str| {
+            device
+                .attr_path(attr)
+                .to_str()
+                .ok_or_else(|| types::QosError::InvalidPath(format!("{}/{attr}", device.name)))
+                .map(str::to_string)
+        };
+        Ok(Self {
+            disksize: monitored_file::MonitoredFile::new(&open("disksize")?)?,
+            mm_stat: monitored_file::MonitoredFile::new(&open("mm_stat")?)?,
+            io_stat: open("io_stat")
+                .ok()
+                .and_then(|p| monitored_file::MonitoredFile::new(&p).ok()),
+        })
+    }
+    pub fn read_stats(&mut self) -> Result<ZramStats, types::QosError> {
+        let disksize = self.disksize.read_value()?.trim().parse().unwrap_or(0);
+        let mm = parse_fields::<8>(self.mm_stat.read_value()?);
+        let io = match self.io_stat.as_mut() {
+            Some(monitor) => parse_fields::<2>(monitor.read_value()?),
+            None => [0; 2],
+        };
+        Ok(ZramStats {
+            disksize,
+            orig_data_size: mm[0],
+            compr_data_size: mm[1],
+            mem_used_total: mm[2],
+            mem_limit: mm[3],
+            same_pages: mm[5],
+            huge_pages: mm[7],
+            failed_reads: io[0],
+            failed_writes: io[1],
+        })
+    }
+}
@@ -1,6 +1,8 @@
 //! Author: [Seclususs](https://github.com/seclususs)
 
-use std::borrow;
+use crate::daemon::state;
+
+use std::{borrow, sync};
 
 #[derive(Clone)]
 pub struct FileTweak {
@@ -27,7 +29,6 @@ pub fn generate_file_tweaks() -> Vec<FileTweak> {
     let mut tweaks = Vec::with_capacity(40);
     tweaks.extend_from_slice(&[
         FileTweak::new_static("/proc/sys/vm/oom_dump_tasks", "0"),
-        FileTweak::new_static("/proc/sys/vm/swappiness", "40"),
         FileTweak::new_static("/proc/sys/vm/vfs_cache_pressure", "100"),
         FileTweak::new_static("/proc/sys/kernel/printk", "0 0 0 0"),
         FileTweak::new_static("/proc/sys/kernel/printk_devkmsg", "off"),
@@ -59,6 +60,10 @@ pub fn generate_file_tweaks() -> Vec<FileTweak> {
         FileTweak::new_static("/proc/sys/debug/exception-trace", "0"),
         // FileTweak::new_static("/proc/sys/net/ipv4/tcp_congestion_control", "westwood"),
     ]);
+    // The zram controller owns swappiness while it runs.
+    if !state::ZRAM_SERVICE_ENABLED.load(sync::atomic::Ordering::Acquire) {
+        tweaks.push(FileTweak::new_static("/proc/sys/vm/swappiness", "40"));
+    }
     tweaks.extend(super::scheduler_io::generate_scheduler_tweaks());
     tweaks
 }
@@ -1,12 +1,27 @@
 //! Author: [Seclususs](https://github.com/seclususs)
 
+use crate::resources::sys_paths;
+
 use std::{collections, fs, path, sync};
 
-static STORAGE_DEV: sync::OnceLock<String> = sync::OnceLock::new();
-static READ_AHEAD_PATH: sync::OnceLock<path::PathBuf> = sync::OnceLock::new();
-static NR_REQUESTS_PATH: sync::OnceLock<path::PathBuf> = sync::OnceLock::new();
-static DISKSTATS_PATH: sync::OnceLock<path::PathBuf> = sync::OnceLock::new();
-static CPU_ZONE_PATH: sync::OnceLock<path::PathBuf> = sync::OnceLock::new();
+static BLOCK_DEVICES: sync::OnceLock<Vec<BlockDevice>> = sync::OnceLock::new();
+static DM_DEVICES: sync::OnceLock<Vec<DmDevice>> = sync::OnceLock::new();
+static ZRAM_DEVICES: sync::OnceLock<Vec<ZramDevice>> = sync::OnceLock::new();
+static F2FS_DEVICES: sync::OnceLock<Vec<F2fsDevice>> = sync::OnceLock::new();
+static BACKLIGHT_PATH: sync::OnceLock<Option<path::PathBuf>> = sync::OnceLock::new();
+static CPU_ZONE_CANDIDATES: sync::OnceLock<Vec<path::PathBuf>> = sync::OnceLock::new();
+static BATTERY_ZONE_CANDIDATES: sync::OnceLock<Vec<path::PathBuf>> = sync::OnceLock::new();
+static CPU_CLUSTERS: sync::OnceLock<Vec<CpuCluster>> = sync::OnceLock::new();
+static SCHEDULER_PROFILE: sync::OnceLock<SchedulerProfile> = sync::OnceLock::new();
+static THERMAL_ZONES: sync::OnceLock<Vec<ThermalZone>> = sync::OnceLock::new();
+static CHARGE_CONTROL: sync::OnceLock<ChargeControl> = sync::OnceLock::new();
+
+const MAX_CPUS: u32 = 16;
+const MIN_BLOCK_SECTORS: u64 = 1 << 17;
+const IGNORED_BLOCK_PREFIXES: &[&str] = &["loop", "ram", "zram", "dm-", "md", "sr"];
+const IGNORED_BLOCK_MARKERS: &[&str] = &["boot", "rpmb"];
+const MAX_THERMAL_ZONES: usize = 24;
+const MAX_CPU_TEMP_CANDIDATES: usize = 4;
 
 const THERMAL_PRIORITY_LIST: &[&str] = &[
     "cpu-1-0-usr",
@@ -42,6 +57,14 @@ const THERMAL_PRIORITY_LIST: &[&str] = &[
     "tsens_tz_sensor0",
 ];
 
+const ZONE_BATTERY_KEYS: &[&str] = &["battery", "bms", "bat_", "batt"];
+const ZONE_MODEM_KEYS: &[&str] = &["modem", "mdm", "pa_therm", "pa-therm", "sdx"];
+const ZONE_GPU_KEYS: &[&str] = &["gpu", "mali", "kgsl", "g3d"];
+const ZONE_SKIN_KEYS: &[&str] = &[
+    "skin", "xo_therm", "xo-therm", "quiet", "shell", "case", "board",
+];
+const ZONE_CPU_KEYS: &[&str] = &["cpu", "cluster", "core", "soc", "tsens", "mtkts", "exynos"];
+
 const THERMAL_BLACKLIST: &[&str] = &[
     "battery",
     "bms",
@@ -65,50 +88,484 @@ const THERMAL_BLACKLIST: &[&str] = &[
     "backlight",
 ];
 
-pub(crate) fn get_storage_name() -> &'static str {
-    STORAGE_DEV.get_or_init(detect_storage_device)
+const CHARGE_SUPPLY_PRIORITY: &[&str] = &["battery", "bms", "main", "usb", "ac"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChargeSwitchKind {
+    InputSuspend,
+    ChargingEnabled,
+    BatteryChargingEnabled,
 }
 
-pub fn get_read_ahead_path() -> &'static path::Path {
-    READ_AHEAD_PATH.get_or_init(|| {
-        path::PathBuf::from(format!(
-            "/sys/block/{}/queue/read_ahead_kb",
-            get_storage_name()
-        ))
-    })
+impl ChargeSwitchKind {
+    const ALL: [ChargeSwitchKind; 3] = [
+        ChargeSwitchKind::InputSuspend,
+        ChargeSwitchKind::ChargingEnabled,
+        ChargeSwitchKind::BatteryChargingEnabled,
+    ];
+    pub fn node(self) -> &'static str {
+        match self {
+            ChargeSwitchKind::InputSuspend => "input_suspend",
+            ChargeSwitchKind::ChargingEnabled => "charging_enabled",
+            ChargeSwitchKind::BatteryChargingEnabled => "battery_charging_enabled",
+        }
+    }
+    pub fn value(self, charging: bool) -> &'static str {
+        let raw = match self {
+            ChargeSwitchKind::InputSuspend => !charging,
+            ChargeSwitchKind::ChargingEnabled | ChargeSwitchKind::BatteryChargingEnabled => {
+                charging
+            }
+        };
+        if raw { "1" } else { "0" }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ChargeControl {
+    pub switch: Option<(path::PathBuf, ChargeSwitchKind)>,
+    pub current_max: Option<path::PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageClass {
+    Rotational,
+    Nvme,
+    Emmc,
+    Ufs,
+}
+
+impl StorageClass {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StorageClass::Rotational => "rotational",
+            StorageClass::Nvme => "nvme",
+            StorageClass::Emmc => "emmc",
+            StorageClass::Ufs => "ufs",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BlockDevice {
+    pub name: String,
+    pub class: StorageClass,
+}
+
+impl BlockDevice {
+    pub fn queue_path(&self, knob: &str) -> path::PathBuf {
+        path::Path::new(sys_paths::K_SYS_BLOCK_DIR)
+            .join(&self.name)
+            .join("queue")
+            .join(knob)
+    }
+    pub fn stat_path(&self) -> path::PathBuf {
+        path::Path::new(sys_paths::K_SYS_BLOCK_DIR)
+            .join(&self.name)
+            .join("stat")
+    }
+    pub fn available_schedulers(&self) -> Vec<String> {
+        fs::read_to_string(self.queue_path("scheduler"))
+            .unwrap_or_default()
+            .split_ascii_whitespace()
+            .map(|s| s.trim_start_matches('[').trim_end_matches(']').to_string())
+            .collect()
+    }
+    pub fn active_scheduler(&self) -> Option<String> {
+        let content = fs::read_to_string(self.queue_path("scheduler")).ok()?;
+        let start = content.find('[')?;
+        let end = content[start..].find(']')?;
+        Some(content[start + 1..start + end].to_string())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ZramDevice {
+    pub name: String,
+}
+
+impl ZramDevice {
+    pub fn attr_path(&self, attr: &str) -> path::PathBuf {
+        path::Path::new(sys_paths::K_SYS_BLOCK_DIR)
+            .join(&self.name)
+            .join(attr)
+    }
+    pub fn supports(&self, attr: &str) -> bool {
+        self.attr_path(attr).exists()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct F2fsDevice {
+    pub name: String,
+    pub mount_point: Option<String>,
+}
+
+impl F2fsDevice {
+    pub fn attr_path(&self, attr: &str) -> path::PathBuf {
+        path::Path::new(sys_paths::K_SYS_FS_F2FS_DIR)
+            .join(&self.name)
+            .join(attr)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DmDevice {
+    pub name: String,
+    pub backing: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CpuCluster {
+    pub cpus: Vec<u32>,
+    pub capacity: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FairScheduler {
+    Cfs,
+    Eevdf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadTracking {
+    Walt,
+    Pelt,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SchedulerProfile {
+    pub fair: FairScheduler,
+    pub tracking: LoadTracking,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoneClass {
+    Cpu,
+    Gpu,
+    Skin,
+    Battery,
+    Modem,
+}
+
+impl ZoneClass {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ZoneClass::Cpu => "cpu",
+            ZoneClass::Gpu => "gpu",
+            ZoneClass::Skin => "skin",
+            ZoneClass::Battery => "battery",
+            ZoneClass::Modem => "modem",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ThermalZone {
+    pub name: String,
+    pub temp_path: path::PathBuf,
+    pub class: ZoneClass,
+}
+
+pub fn get_block_devices() -> &'static [BlockDevice] {
+    BLOCK_DEVICES.get_or_init(detect_block_devices)
+}
+
+pub fn get_dm_devices() -> &'static [DmDevice] {
+    DM_DEVICES.get_or_init(detect_dm_devices)
+}
+
+pub fn get_zram_devices() -> &'static [ZramDevice] {
+    ZRAM_DEVICES.get_or_init(detect_zram_devices)
+}
+
+pub fn get_f2fs_devices() -> &'static [F2fsDevice] {
+    F2FS_DEVICES.get_or_init(detect_f2fs_devices)
+}
+
+pub fn get_backlight_path() -> Option<&'static path::Path> {
+    BACKLIGHT_PATH.get_or_init(detect_backlight_path).as_deref()
 }
 
-pub fn get_nr_requests_path() -> &'static path::Path {
-    NR_REQUESTS_PATH.get_or_init(|| {
-        path::PathBuf::from(format!(
-            "/sys/block/{}/queue/nr_requests",
-            get_storage_name()
-        ))
+pub fn get_cpu_temp_candidates() -> &'static [path::PathBuf] {
+    CPU_ZONE_CANDIDATES.get_or_init(detect_cpu_thermal_candidates)
+}
+
+pub fn get_battery_temp_candidates() -> &'static [path::PathBuf] {
+    BATTERY_ZONE_CANDIDATES.get_or_init(detect_battery_thermal_candidates)
+}
+
+pub fn get_cpu_clusters() -> &'static [CpuCluster] {
+    CPU_CLUSTERS.get_or_init(detect_cpu_clusters)
+}
+
+pub fn get_thermal_zones() -> &'static [ThermalZone] {
+    THERMAL_ZONES.get_or_init(detect_thermal_zones)
+}
+
+pub fn get_charge_control() -> &'static ChargeControl {
+    CHARGE_CONTROL.get_or_init(detect_charge_control)
+}
+
+pub fn get_scheduler_profile() -> SchedulerProfile {
+    *SCHEDULER_PROFILE.get_or_init(detect_scheduler_profile)
+}
+
+pub fn kernel_version() -> (u32, u32) {
+    let release = fs::read_to_string(sys_paths::K_KERNEL_OSRELEASE).unwrap_or_default();
+    let mut parts = release
+        .trim()
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|v| v.parse::<u32>().ok());
+    (parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+}
+
+fn detect_scheduler_profile() -> SchedulerProfile {
+    let any_exists = |paths: &[&str]| paths.iter().any(|p| path::Path::new(p).exists());
+    let has_cfs_knobs = path::Path::new(sys_paths::K_SCHED_LATENCY_NS).exists();
+    let has_base_slice = any_exists(sys_paths::K_SCHED_BASE_SLICE_CANDIDATES);
+    let fair = if has_base_slice || (!has_cfs_knobs && kernel_version() >= (6, 6)) {
+        FairScheduler::Eevdf
+    } else {
+        FairScheduler::Cfs
+    };
+    let tracking = if any_exists(sys_paths::K_SCHED_WALT_INIT_CANDIDATES)
+        || path::Path::new("/proc/sys/walt").exists()
+    {
+        LoadTracking::Walt
+    } else {
+        LoadTracking::Pelt
+    };
+    SchedulerProfile { fair, tracking }
+}
+
+fn detect_charge_control() -> ChargeControl {
+    let base_dir = path::Path::new(sys_paths::K_POWER_SUPPLY_DIR);
+    let mut supplies: Vec<path::PathBuf> = fs::read_dir(base_dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    let rank = |p: &path::PathBuf| {
+        let name = p.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        CHARGE_SUPPLY_PRIORITY
+            .iter()
+            .position(|&s| s == name)
+            .unwrap_or(CHARGE_SUPPLY_PRIORITY.len())
+    };
+    supplies.sort_by_key(|p| (rank(p), p.clone()));
+    let mut control = ChargeControl::default();
+    for supply in &supplies {
+        if control.switch.is_none() {
+            control.switch = ChargeSwitchKind::ALL.iter().find_map(|&kind| {
+                let node = supply.join(kind.node());
+                node.exists().then_some((node, kind))
+            });
+        }
+        if control.current_max.is_none() {
+            let node = supply.join("constant_charge_current_max");
+            if node.exists() {
+                control.current_max = Some(node);
+            }
+        }
+    }
+    control
+}
+
+fn read_u64(path: &str) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse::<u64>().ok()
+}
+
+fn detect_cpu_clusters() -> Vec<CpuCluster> {
+    let mut clusters: Vec<CpuCluster> = Vec::new();
+    for cpu in 0..MAX_CPUS {
+        let base = format!("/sys/devices/system/cpu/cpu{cpu}");
+        if !path::Path::new(&base).exists() {
+            break;
+        }
+        if clusters.iter().any(|c| c.cpus.contains(&cpu)) {
+            continue;
+        }
+        let capacity = read_u64(&format!("{base}/cpu_capacity"))
+            .or_else(|| read_u64(&format!("{base}/cpufreq/cpuinfo_max_freq")))
+            .unwrap_or(0);
+        let mut cpus: Vec<u32> = fs::read_to_string(format!("{base}/cpufreq/related_cpus"))
+            .map(|content| {
+                content
+                    .split_whitespace()
+                    .filter_map(|v| v.parse::<u32>().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !cpus.contains(&cpu) {
+            cpus.push(cpu);
+        }
+        cpus.sort_unstable();
+        clusters.push(CpuCluster { cpus, capacity });
+    }
+    clusters.sort_by_key(|c| c.capacity);
+    clusters
+}
+
+fn detect_backlight_path() -> Option<path::PathBuf> {
+    let mut panels: Vec<path::PathBuf> = fs::read_dir(sys_paths::K_BACKLIGHT_DIR)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path().join("brightness"))
+                .filter(|p| p.exists())
+                .collect()
+        })
+        .unwrap_or_default();
+    panels.sort();
+    panels.into_iter().next().or_else(|| {
+        let legacy = path::PathBuf::from(sys_paths::K_LCD_BACKLIGHT_PATH);
+        legacy.exists().then_some(legacy)
     })
 }
 
-pub fn get_diskstats_path() -> &'static path::Path {
-    DISKSTATS_PATH
-        .get_or_init(|| path::PathBuf::from(format!("/sys/block/{}/stat", get_storage_name())))
+fn classify_block_device(name: &str) -> StorageClass {
+    let rotational = fs::read_to_string(format!(
+        "{}/{name}/queue/rotational",
+        sys_paths::K_SYS_BLOCK_DIR
+    ))
+    .is_ok_and(|s| s.trim() == "1");
+    if rotational {
+        StorageClass::Rotational
+    } else if name.starts_with("nvme") {
+        StorageClass::Nvme
+    } else if name.starts_with("mmcblk") {
+        StorageClass::Emmc
+    } else {
+        StorageClass::Ufs
+    }
 }
 
-pub fn get_cpu_temp_path() -> &'static path::Path {
-    CPU_ZONE_PATH.get_or_init(detect_cpu_thermal_path)
+fn detect_block_devices() -> Vec<BlockDevice> {
+    let base_dir = path::Path::new(sys_paths::K_SYS_BLOCK_DIR);
+    let Ok(entries) = fs::read_dir(base_dir) else {
+        return Vec::new();
+    };
+    let mut devices: Vec<BlockDevice> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            if IGNORED_BLOCK_PREFIXES.iter().any(|p| name.starts_with(p))
+                || IGNORED_BLOCK_MARKERS.iter().any(|m| name.contains(m))
+            {
+                return None;
+            }
+            let dir = base_dir.join(&name);
+            let sectors = read_u64(dir.join("size").to_str()?)?;
+            if sectors < MIN_BLOCK_SECTORS || !dir.join("queue/read_ahead_kb").exists() {
+                return None;
+            }
+            let class = classify_block_device(&name);
+            Some(BlockDevice { name, class })
+        })
+        .collect();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+fn detect_zram_devices() -> Vec<ZramDevice> {
+    let base_dir = path::Path::new(sys_paths::K_SYS_BLOCK_DIR);
+    let Ok(entries) = fs::read_dir(base_dir) else {
+        return Vec::new();
+    };
+    let mut devices: Vec<ZramDevice> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            (name.starts_with("zram") && base_dir.join(&name).join("mm_stat").exists())
+                .then_some(ZramDevice { name })
+        })
+        .collect();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+fn detect_f2fs_devices() -> Vec<F2fsDevice> {
+    let Ok(entries) = fs::read_dir(sys_paths::K_SYS_FS_F2FS_DIR) else {
+        return Vec::new();
+    };
+    let mounts = fs::read_to_string(sys_paths::K_PROC_MOUNTS).unwrap_or_default();
+    let mount_point_of = |name: &str| {
+        mounts.lines().find_map(|line| {
+            let mut fields = line.split_ascii_whitespace();
+            let source = fields.next()?;
+            let target = fields.next()?;
+            if fields.next()? != "f2fs" {
+                return None;
+            }
+            let resolved = fs::canonicalize(source).ok()?;
+            (resolved.file_name()?.to_str()? == name).then(|| target.to_string())
+        })
+    };
+    let mut devices: Vec<F2fsDevice> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            if name == "features" || !entry.path().join("dirty_segments").exists() {
+                return None;
+            }
+            let mount_point = mount_point_of(&name);
+            Some(F2fsDevice { name, mount_point })
+        })
+        .collect();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
 }
 
-fn detect_storage_device() -> String {
-    let candidates = ["nvme0n1", "sda", "sdb", "mmcblk0"];
-    for &dev in &candidates {
-        if path::Path::new("/sys/block").join(dev).exists() {
-            return dev.to_string();
+fn resolve_backing_disks(name: &str, depth: u32, out: &mut Vec<String>) {
+    if depth > 4 {
+        return;
+    }
+    let base_dir = path::Path::new(sys_paths::K_SYS_BLOCK_DIR);
+    if name.starts_with("dm-") {
+        if let Ok(slaves) = fs::read_dir(base_dir.join(name).join("slaves")) {
+            for slave in slaves.flatten() {
+                if let Some(slave_name) = slave.file_name().to_str() {
+                    resolve_backing_disks(slave_name, depth + 1, out);
+                }
+            }
         }
+        return;
+    }
+    let disk = if base_dir.join(name).exists() {
+        Some(name.to_string())
+    } else {
+        fs::canonicalize(path::Path::new(sys_paths::K_SYS_CLASS_BLOCK_DIR).join(name))
+            .ok()
+            .and_then(|p| Some(p.parent()?.file_name()?.to_str()?.to_string()))
+    };
+    if let Some(disk) = disk
+        && !out.contains(&disk)
+    {
+        out.push(disk);
     }
-    "mmcblk0".to_string()
 }
 
-fn detect_cpu_thermal_path() -> path::PathBuf {
-    let base_dir = path::Path::new("/sys/class/thermal");
-    let mut zones_map: collections::HashMap<String, String> = collections::HashMap::new();
+fn detect_dm_devices() -> Vec<DmDevice> {
+    let Ok(entries) = fs::read_dir(sys_paths::K_SYS_BLOCK_DIR) else {
+        return Vec::new();
+    };
+    let mut devices: Vec<DmDevice> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            if !name.starts_with("dm-") {
+                return None;
+            }
+            let mut backing = Vec::new();
+            resolve_backing_disks(&name, 0, &mut backing);
+            (!backing.is_empty()).then_some(DmDevice { name, backing })
+        })
+        .collect();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+fn scan_thermal_zone_types(base_dir: &path::Path) -> Vec<(String, String)> {
+    let mut zones = Vec::new();
     if let Ok(entries) = fs::read_dir(base_dir) {
         for entry in entries.flatten() {
             let path = entry.path();
@@ -116,32 +573,103 @@ fn detect_cpu_thermal_path() -> path::PathBuf {
             if file_name.starts_with("thermal_zone")
                 && let Ok(content) = fs::read_to_string(path.join("type"))
             {
-                let type_name = content.trim().to_string();
-                zones_map.insert(type_name, file_name.to_string());
+                zones.push((content.trim().to_string(), file_name.to_string()));
             }
         }
     }
+    zones
+}
+
+fn classify_zone(type_name: &str) -> Option<ZoneClass> {
+    let name_lower = type_name.to_lowercase();
+    let matches = |keys: &[&str]| keys.iter().any(|&k| name_lower.contains(k));
+    if matches(ZONE_BATTERY_KEYS) {
+        Some(ZoneClass::Battery)
+    } else if matches(ZONE_MODEM_KEYS) {
+        Some(ZoneClass::Modem)
+    } else if matches(ZONE_GPU_KEYS) {
+        Some(ZoneClass::Gpu)
+    } else if matches(ZONE_SKIN_KEYS) {
+        Some(ZoneClass::Skin)
+    } else if matches(ZONE_CPU_KEYS)
+        || THERMAL_PRIORITY_LIST
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(type_name))
+    {
+        Some(ZoneClass::Cpu)
+    } else {
+        None
+    }
+}
+
+fn detect_thermal_zones() -> Vec<ThermalZone> {
+    let base_dir = path::Path::new("/sys/class/thermal");
+    let mut zones: Vec<ThermalZone> = scan_thermal_zone_types(base_dir)
+        .into_iter()
+        .filter_map(|(type_name, dir_name)| {
+            let class = classify_zone(&type_name)?;
+            Some(ThermalZone {
+                name: type_name,
+                temp_path: base_dir.join(dir_name).join("temp"),
+                class,
+            })
+        })
+        .collect();
+    zones.sort_by(|a, b| a.temp_path.cmp(&b.temp_path));
+    zones.truncate(MAX_THERMAL_ZONES);
+    zones
+}
+
+fn detect_cpu_thermal_candidates() -> Vec<path::PathBuf> {
+    let base_dir = path::Path::new("/sys/class/thermal");
+    let zones_map: collections::HashMap<String, String> =
+        scan_thermal_zone_types(base_dir).into_iter().collect();
+    let mut candidates: Vec<path::PathBuf> = Vec::new();
+    let mut push = |filename: &String| {
+        let temp_path = base_dir.join(filename).join("temp");
+        if !candidates.contains(&temp_path) && candidates.len() < MAX_CPU_TEMP_CANDIDATES {
+            candidates.push(temp_path);
+        }
+    };
     for &target in THERMAL_PRIORITY_LIST {
         if let Some(filename) = zones_map.get(target) {
-            return base_dir.join(filename).join("temp");
-        }
-        if let Some((_, filename)) = zones_map
+            push(filename);
+        } else if let Some((_, filename)) = zones_map
             .iter()
             .find(|(k, _)| k.eq_ignore_ascii_case(target))
         {
-            return base_dir.join(filename).join("temp");
+            push(filename);
         }
     }
-    for (type_name, filename) in &zones_map {
-        let name_lower = type_name.to_lowercase();
-        let looks_like_cpu = name_lower.contains("cpu")
-            || name_lower.contains("soc")
-            || name_lower.contains("cluster")
-            || name_lower.contains("ap");
-        let is_safe = !THERMAL_BLACKLIST.iter().any(|&b| name_lower.contains(b));
-        if looks_like_cpu && is_safe {
-            return base_dir.join(filename).join("temp");
-        }
+    let mut heuristic: Vec<(&String, &String)> = zones_map
+        .iter()
+        .filter(|(type_name, _)| {
+            let name_lower = type_name.to_lowercase();
+            let looks_like_cpu = name_lower.contains("cpu")
+                || name_lower.contains("soc")
+                || name_lower.contains("cluster")
+                || name_lower.contains("ap");
+            let is_safe = !THERMAL_BLACKLIST.iter().any(|&b| name_lower.contains(b));
+            looks_like_cpu && is_safe
+        })
+        .collect();
+    heuristic.sort_by(|a, b| a.1.cmp(b.1));
+    for (_, filename) in heuristic {
+        push(filename);
     }
-    base_dir.join("thermal_zone3").join("temp")
+    if candidates.is_empty() {
+        candidates.push(base_dir.join("thermal_zone3").join("temp"));
+    }
+    candidates
+}
+
+fn detect_battery_thermal_candidates() -> Vec<path::PathBuf> {
+    let mut candidates = vec![path::PathBuf::from(sys_paths::K_BATTERY_TEMP_PATH)];
+    candidates.extend(
+        get_thermal_zones()
+            .iter()
+            .filter(|zone| zone.class == ZoneClass::Battery)
+            .map(|zone| zone.temp_path.clone()),
+    );
+    candidates
 }
@@ -1,11 +1,16 @@
 //! Author: [Seclususs](https://github.com/seclususs)
 
 pub use crate::resources::discovery::{
-    get_cpu_temp_path, get_diskstats_path, get_nr_requests_path, get_read_ahead_path,
+    get_backlight_path, get_battery_temp_candidates, get_block_devices, get_charge_control,
+    get_cpu_clusters, get_cpu_temp_candidates, get_dm_devices, get_f2fs_devices,
+    get_scheduler_profile, get_thermal_zones, get_zram_devices,
 };
 
 pub const K_PSI_CPU_PATH: &str = "/proc/pressure/cpu";
 pub const K_PSI_IO_PATH: &str = "/proc/pressure/io";
+pub const K_PSI_MEM_PATH: &str = "/proc/pressure/memory";
+pub const K_PROC_STAT_PATH: &str = "/proc/stat";
+pub const K_PROC_DIR: &str = "/proc";
 
 pub const K_SCHED_LATENCY_NS: &str = "/proc/sys/kernel/sched_latency_ns";
 pub const K_SCHED_MIN_GRANULARITY_NS: &str = "/proc/sys/kernel/sched_min_granularity_ns";
@@ -13,6 +18,73 @@ pub const K_SCHED_WAKEUP_GRANULARITY_NS: &str = "/proc/sys/kernel/sched_wakeup_g
 pub const K_SCHED_MIGRATION_COST_NS: &str = "/proc/sys/kernel/sched_migration_cost_ns";
 pub const K_SCHED_WALT_INIT_TASK_LOAD_PCT: &str = "/proc/sys/kernel/sched_walt_init_task_load_pct";
 pub const K_SCHED_UCLAMP_UTIL_MIN: &str = "/proc/sys/kernel/sched_uclamp_util_min";
+pub const K_SCHED_FEATURES: &str = "/sys/kernel/debug/sched/features";
+pub const K_KERNEL_OSRELEASE: &str = "/proc/sys/kernel/osrelease";
+
+pub const K_SCHED_BASE_SLICE_CANDIDATES: &[&str] = &[
+    "/proc/sys/kernel/sched_base_slice_ns",
+    "/sys/kernel/debug/sched/base_slice_ns",
+];
+pub const K_SCHED_MIGRATION_COST_CANDIDATES: &[&str] = &[
+    K_SCHED_MIGRATION_COST_NS,
+    "/sys/kernel/debug/sched/migration_cost_ns",
+];
+pub const K_SCHED_WALT_INIT_CANDIDATES: &[&str] = &[
+    K_SCHED_WALT_INIT_TASK_LOAD_PCT,
+    "/proc/sys/walt/sched_init_task_load_pct",
+];
+
+pub const K_CPUCTL_TOP_APP: &str = "/dev/cpuctl/top-app";
+pub const K_TOP_APP_PROCS_CANDIDATES: &[&str] = &[
+    "/dev/cpuset/top-app/cgroup.procs",
+    "/dev/cpuctl/top-app/cgroup.procs",
+];
+pub const K_CPUCTL_FOREGROUND: &str = "/dev/cpuctl/foreground";
+pub const K_CPUCTL_BACKGROUND: &str = "/dev/cpuctl/background";
+pub const K_CPUCTL_SYSTEM_BACKGROUND: &str = "/dev/cpuctl/system-background";
+pub const K_CGROUP_UCLAMP_MIN: &str = "cpu.uclamp.min";
+pub const K_CGROUP_UCLAMP_MAX: &str = "cpu.uclamp.max";
+
+pub const K_CPUSET_BACKGROUND_CPUS: &str = "/dev/cpuset/background/cpus";
+pub const K_CPUSET_SYSTEM_BACKGROUND_CPUS: &str = "/dev/cpuset/system-background/cpus";
+pub const K_CPUSET_RESTRICTED_CPUS: &str = "/dev/cpuset/restricted/cpus";
 
 pub const K_BATTERY_TEMP_PATH: &str = "/sys/class/power_supply/battery/temp";
 pub const K_BATTERY_CAPACITY_PATH: &str = "/sys/class/power_supply/battery/capacity";
+pub const K_BATTERY_CURRENT_NOW_PATH: &str = "/sys/class/power_supply/battery/current_now";
+pub const K_BATTERY_VOLTAGE_NOW_PATH: &str = "/sys/class/power_supply/battery/voltage_now";
+pub const K_BATTERY_CHARGE_COUNTER_PATH: &str = "/sys/class/power_supply/battery/charge_counter";
+pub const K_BATTERY_STATUS_PATH: &str = "/sys/class/power_supply/battery/status";
+
+pub const K_VM_DIRTY_RATIO: &str = "/proc/sys/vm/dirty_ratio";
+pub const K_VM_DIRTY_BACKGROUND_RATIO: &str = "/proc/sys/vm/dirty_background_ratio";
+pub const K_VM_DIRTY_EXPIRE_CS: &str = "/proc/sys/vm/dirty_expire_centisecs";
+pub const K_VM_DIRTY_WRITEBACK_CS: &str = "/proc/sys/vm/dirty_writeback_centisecs";
+pub const K_VM_SWAPPINESS: &str = "/proc/sys/vm/swappiness";
+
+pub const K_BACKLIGHT_DIR: &str = "/sys/class/backlight";
+pub const K_LCD_BACKLIGHT_PATH: &str = "/sys/class/leds/lcd-backlight/brightness";
+
+pub const K_SYS_BLOCK_DIR: &str = "/sys/block";
+pub const K_SYS_CLASS_BLOCK_DIR: &str = "/sys/class/block";
+pub const K_SYS_FS_F2FS_DIR: &str = "/sys/fs/f2fs";
+pub const K_PROC_MOUNTS: &str = "/proc/mounts";
+
+pub const K_MODULE_CONFIG_PATH: &str = "/data/adb/modules/sys_qos/config.ini";
+pub const K_PACKAGES_LIST: &str = "/data/system/packages.list";
+pub const K_PACKAGES_XML: &str = "/data/system/packages.xml";
+pub const K_SYSTEM_USERS_DIR: &str = "/data/system/users";
+pub const K_DATA_USER_DIR: &str = "/data/user";
+pub const K_DATA_MEDIA_DIR: &str = "/data/media";
+pub const K_DATA_APP_DIR: &str = "/data/app";
+pub const K_DALVIK_CACHE_DIR: &str = "/data/dalvik-cache";
+pub const K_CLEANER_RULES_PATH: &str = "/data/adb/modules/sys_qos/cleaner_rules.conf";
+pub const K_POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+pub const K_STATE_DIR: &str = "/data/adb/sys_qos";
+pub const K_STATUS_PATH: &str = "/data/adb/sys_qos/status";
+pub const K_THERMAL_PROFILE_PATH: &str = "/data/adb/sys_qos/thermal_profile";
+pub const K_TRIM_STATE_PATH: &str = "/data/adb/sys_qos/trim_state";
+pub const K_CLEANER_STATE_PATH: &str = "/data/adb/sys_qos/cleaner_state";
+pub const K_CLEANER_REPORT_PATH: &str = "/data/adb/sys_qos/cleaner_report";
+pub const K_CLEANER_QUARANTINE_PATH: &str = "/data/adb/sys_qos/cleaner_quarantine";
@@ -12,6 +12,55 @@ pub fn to_cstring(s: &str) -> Result<ffi::CString, types::QosError> {
 #[inline]
 pub fn validate_value(value: &str) -> bool {
     value.bytes().all(|c| {
-        c.is_ascii_alphanumeric() || c == b'.' || c == b'-' || c == b'_' || c == b'=' || c == b' '
+        c.is_ascii_alphanumeric()
+            || c == b'.'
+            || c == b'-'
+            || c == b'_'
+            || c == b'='
+            || c == b' '
+            || c == b','
     })
 }
+
+pub fn parse_cpu_list(list: &str) -> Vec<u32> {
+    let mut cpus = Vec::new();
+    for part in list
+        .trim()
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        let (start, end) = part.split_once('-').unwrap_or((part, part));
+        if let (Ok(start), Ok(end)) = (start.trim().parse::<u32>(), end.trim().parse::<u32>()) {
+            cpus.extend(start..=end);
+        }
+    }
+    cpus
+}
+
+pub fn format_cpu_list(cpus: &[u32]) -> String {
+    let mut sorted = cpus.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let mut out = String::with_capacity(sorted.len() * 3);
+    let mut itoa_buf = itoa::Buffer::new();
+    let mut idx = 0;
+    while idx < sorted.len() {
+        let start = sorted[idx];
+        let mut end = start;
+        while idx + 1 < sorted.len() && sorted[idx + 1] == end + 1 {
+            idx += 1;
+            end = sorted[idx];
+        }
+        if !out.is_empty() {
+            out.push(',');
+        }
+        out.push_str(itoa_buf.format(start));
+        if end != start {
+            out.push('-');
+            out.push_str(itoa_buf.format(end));
+        }
+        idx += 1;
+    }
+    out
+}
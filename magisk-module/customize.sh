@@ -70,6 +70,7 @@ backup_config() {
   if [ -f "$ACTIVE_DIR/config.ini" ]; then
     ui_print_log "Detecting previous installation..."
     cp -f "$ACTIVE_DIR/config.ini" "$TMPDIR/config.ini.bak"
+    [ -f "$ACTIVE_DIR/cleaner_rules.conf" ] && cp -f "$ACTIVE_DIR/cleaner_rules.conf" "$TMPDIR/cleaner_rules.conf.bak"
     ui_print_info "Old config backed up."
     return 0
   fi
@@ -88,10 +89,19 @@ restore_config() {
       local val=$(echo "$line" | cut -d'=' -f2-)
       if grep -q "^$key=" "$NEW_CFG"; then
         sed -i "s|^$key=.*|$key=$val|" "$NEW_CFG"
+      else
+        case "$key" in
+          cleaner_user_*) echo "$key=$val" >> "$NEW_CFG" ;;
+        esac
       fi
     done
 
     ui_print_info "Config merged. User settings restored."
+
+    if [ -f "$TMPDIR/cleaner_rules.conf.bak" ]; then
+      cp -f "$TMPDIR/cleaner_rules.conf.bak" "$MODPATH/cleaner_rules.conf"
+      ui_print_info "Cleaner rules restored."
+    fi
   else
     ui_print_log "Using default configuration."
   fi
@@ -101,7 +111,7 @@ run_setup_wizard() {
   ui_print_log "Starting Setup..."
   ui_print " "
   
-  FEATURES="Blocker:blocker_enabled Cleaner:cleaner_enabled CPU_Controller:cpu_enabled Storage_Controller:storage_enabled System_Tweaks:tweaks_enabled"
+  FEATURES="Blocker:blocker_enabled Charge_Limiter:charging_enabled Cleaner:cleaner_enabled CPU_Controller:cpu_enabled Cpuset_Controller:cpuset_enabled F2FS_Maintenance:f2fs_enabled Storage_Controller:storage_enabled System_Tweaks:tweaks_enabled Trim_Service:trim_enabled Zram_Controller:zram_enabled"
   
   for item in $FEATURES; do
     local name=$(echo "$item" | cut -d':' -f1 | tr '_' ' ')
@@ -112,9 +122,24 @@ run_setup_wizard() {
       "cpu_enabled")
          [ ! -e "/proc/pressure/cpu" ] && warning="PSI CPU missing (/proc/pressure/cpu). Service might degrade."
          ;;
+      "cpuset_enabled")
+         [ ! -d "/dev/cpuset" ] && warning="Cpuset hierarchy missing (/dev/cpuset). Service will fail."
+         ;;
       "storage_enabled")
          [ ! -e "/proc/pressure/io" ] && warning="PSI IO missing (/proc/pressure/io). Service will fail."
          ;;
+      "charging_enabled")
+         [ ! -d "/sys/class/power_supply/battery" ] && warning="Battery power supply node missing. Service will fail."
+         ;;
+      "f2fs_enabled")
+         [ ! -d "/sys/fs/f2fs" ] && warning="No f2fs filesystems found. Service will fail."
+         ;;
+      "trim_enabled")
+         [ ! -e "/proc/pressure/io" ] && warning="PSI IO missing (/proc/pressure/io). Service will fail."
+         ;;
+      "zram_enabled")
+         ! ls /sys/block/zram* >/dev/null 2>&1 && warning="No zram devices found. Service will fail."
+         ;;
       "cleaner_enabled")
          if [ ! -d "/data/data" ] || [ ! -d "/proc" ]; then
             warning="System paths inaccessible (/data/data or /proc)"
@@ -206,7 +231,7 @@ backup_config
 HAS_BACKUP=$?
 
 ui_print_log "Extracting module files..."
-unzip -o "$ZIPFILE" 'service.sh' 'system/bin/qos_daemon' 'config.ini' 'system.prop' 'system/product/media/bootanimation.zip' -d "$MODPATH" >&2
+unzip -o "$ZIPFILE" 'service.sh' 'system/bin/qos_daemon' 'config.ini' 'cleaner_rules.conf' 'system.prop' 'system/product/media/bootanimation.zip' -d "$MODPATH" >&2
 unzip -o "$ZIPFILE" 'common/*' -d "$MODPATH" >&2
 
 validate_system_props
@@ -245,6 +270,7 @@ set_perm_recursive "$MODPATH" 0 0 0755 0644
 set_perm "$MODPATH/service.sh" 0 0 0755
 set_perm "$MODPATH/system/bin/qos_daemon" 0 0 0755
 set_perm "$MODPATH/config.ini" 0 0 0644
+set_perm "$MODPATH/cleaner_rules.conf" 0 0 0644
 
 if [ -d "$MODPATH/common" ]; then
   ui_print_log "Running additional scripts..."
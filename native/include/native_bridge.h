@@ -37,6 +37,21 @@ extern "C" {
  */
 void rust_set_blocker_service_enabled(bool enabled);
 
+/**
+ * @brief Configures the enabled state of the Charging Controller service.
+ *
+ * Updates the configuration state for the charge limiter. When enabled, the
+ * service pauses charging at the configured target percentage, resumes it
+ * below the hysteresis band, and lowers the charge current as battery
+ * temperature approaches its hard limit.
+ *
+ * This operation is thread-safe and the new state takes effect immediately
+ * for the next polling cycle.
+ *
+ * @param[in] enabled True to enable the service, false to disable.
+ */
+void rust_set_charging_service_enabled(bool enabled);
+
 /**
  * @brief Configures the enabled state of the Cleaner Controller service.
  *
@@ -62,6 +77,37 @@ void rust_set_cleaner_service_enabled(bool enabled);
  */
 void rust_set_cpu_service_enabled(bool enabled);
 
+/**
+ * @brief Configures the enabled state of the Cpuset Controller service.
+ *
+ * Updates the configuration state for background cpuset management. When
+ * enabled, the background, system-background and restricted groups are
+ * widened or narrowed by cluster according to CPU pressure and thermal
+ * headroom, never beyond the CPUs their original masks allowed.
+ *
+ * This operation is thread-safe and the new state takes effect immediately
+ * for the next polling cycle.
+ *
+ * @param[in] enabled True to enable the service, false to disable.
+ */
+void rust_set_cpuset_service_enabled(bool enabled);
+
+/**
+ * @brief Configures the enabled state of the F2FS Maintenance service.
+ *
+ * Updates the configuration state for f2fs idle maintenance. When enabled,
+ * the service watches dirty and free segment counts of every f2fs volume and
+ * runs urgent garbage collection and discard only while the screen is off,
+ * the device is charging and the battery is cool. Rising I/O pressure stops
+ * it immediately.
+ *
+ * This operation is thread-safe and the new state takes effect immediately
+ * for the next polling cycle.
+ *
+ * @param[in] enabled True to enable the service, false to disable.
+ */
+void rust_set_f2fs_service_enabled(bool enabled);
+
 /**
  * @brief Configures the enabled state of the Storage Controller service.
  *
@@ -84,6 +130,21 @@ void rust_set_storage_service_enabled(bool enabled);
  */
 void rust_set_display_service_enabled(bool enabled);
 
+/**
+ * @brief Configures the enabled state of the Trim service.
+ *
+ * Updates the configuration state for idle-time TRIM. When enabled, the
+ * service issues FITRIM on /data and /cache in bounded ranges while the
+ * screen is off, the device is charging and the battery is cool, pauses as
+ * soon as I/O pressure rises, and persists its progress and last trim time.
+ *
+ * This operation is thread-safe and the new state takes effect immediately
+ * for the next polling cycle.
+ *
+ * @param[in] enabled True to enable the service, false to disable.
+ */
+void rust_set_trim_service_enabled(bool enabled);
+
 /**
  * @brief Configures the enabled state of the System Tweaks module.
  *
@@ -97,6 +158,21 @@ void rust_set_display_service_enabled(bool enabled);
  */
 void rust_set_tweaks_enabled(bool enabled);
 
+/**
+ * @brief Configures the enabled state of the Zram Controller service.
+ *
+ * Updates the configuration state for zram management. When enabled, the
+ * service reports compression statistics for every zram device, selects a
+ * compression algorithm, tunes swappiness from memory pressure and zram fill
+ * level, and triggers idle-page recompression or writeback where supported.
+ *
+ * This operation is thread-safe and the new state takes effect immediately
+ * for the next polling cycle.
+ *
+ * @param[in] enabled True to enable the service, false to disable.
+ */
+void rust_set_zram_service_enabled(bool enabled);
+
 /**
  * @brief Initializes and starts the core service reactor in a background
  * thread.
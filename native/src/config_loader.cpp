@@ -12,10 +12,15 @@ std::map<std::string, bool> load(const char *path) {
   // Initialize defaults to ensure deterministic behavior if the file is missing
   // or empty.
   config["cpu"] = false;
+  config["cpuset"] = false;
   config["io"] = false;
   config["cleaner"] = false;
   config["tweaks"] = false;
   config["blocker"] = false;
+  config["charging"] = false;
+  config["zram"] = false;
+  config["f2fs"] = false;
+  config["trim"] = false;
 
   std::ifstream file(path);
   if (!file.is_open()) {
@@ -74,6 +79,8 @@ std::map<std::string, bool> load(const char *path) {
       // Daemon.
       if (key == "cpu_enabled")
         config["cpu"] = bool_val;
+      else if (key == "cpuset_enabled")
+        config["cpuset"] = bool_val;
       else if (key == "storage_enabled")
         config["io"] = bool_val;
       else if (key == "cleaner_enabled")
@@ -82,6 +89,14 @@ std::map<std::string, bool> load(const char *path) {
         config["tweaks"] = bool_val;
       else if (key == "blocker_enabled")
         config["blocker"] = bool_val;
+      else if (key == "charging_enabled")
+        config["charging"] = bool_val;
+      else if (key == "zram_enabled")
+        config["zram"] = bool_val;
+      else if (key == "f2fs_enabled")
+        config["f2fs"] = bool_val;
+      else if (key == "trim_enabled")
+        config["trim"] = bool_val;
     }
   }
 
@@ -54,10 +54,15 @@ int main(int argc, char *argv[]) {
   auto features = qos::runtime::Diagnostics::check_kernel_features();
 
   bool final_cpu = false;
+  bool final_cpuset = false;
   bool final_io = false;
   bool final_cleaner = false;
   bool final_tweaks = false;
   bool final_blocker = false;
+  bool final_charging = false;
+  bool final_zram = false;
+  bool final_f2fs = false;
+  bool final_trim = false;
 
   // Phase 4: Configuration
   // Enclose in a block scope to ensure 'cfg' (std::map) is destroyed
@@ -68,15 +73,21 @@ int main(int argc, char *argv[]) {
 
     // Reconcile configuration with available kernel features.
     final_cpu = cfg["cpu"] && features.has_cpu_psi;
+    final_cpuset = cfg["cpuset"] && features.has_cpu_psi;
     final_io = cfg["io"] && features.has_io_psi;
     final_cleaner = cfg["cleaner"] && features.cleaner_supported &&
                     features.has_cpu_psi && features.has_io_psi;
     final_tweaks = cfg["tweaks"];
     final_blocker = cfg["blocker"];
+    final_charging = cfg["charging"];
+    final_zram = cfg["zram"];
+    final_f2fs = cfg["f2fs"] && features.has_io_psi;
+    final_trim = cfg["trim"] && features.has_io_psi;
   }
 
-  if (!final_cpu && !final_io && !final_tweaks && !final_cleaner &&
-      !final_blocker) {
+  if (!final_cpu && !final_cpuset && !final_io && !final_tweaks &&
+      !final_cleaner && !final_blocker && !final_charging && !final_zram &&
+      !final_f2fs && !final_trim) {
     LOGE("Daemon shutting down to save resources (No services enabled).");
     return EXIT_FAILURE;
   }
@@ -84,10 +95,15 @@ int main(int argc, char *argv[]) {
   // Phase 5: Service Activation
   LOGI("Activating Services...");
   rust_set_cpu_service_enabled(final_cpu);
+  rust_set_cpuset_service_enabled(final_cpuset);
   rust_set_storage_service_enabled(final_io);
   rust_set_cleaner_service_enabled(final_cleaner);
   rust_set_tweaks_enabled(final_tweaks);
   rust_set_blocker_service_enabled(final_blocker);
+  rust_set_charging_service_enabled(final_charging);
+  rust_set_zram_service_enabled(final_zram);
+  rust_set_f2fs_service_enabled(final_f2fs);
+  rust_set_trim_service_enabled(final_trim);
 
   // Force the allocator to purge dirty pages to minimize the resident set size
   // before locking memory.